@@ -0,0 +1,12 @@
+#![no_main]
+
+use advent_of_code_2022::solver::{PuzzlePart, Solve, Solver};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(solver) = Solver::from_reader(2, data) {
+        for puzzle_part in [PuzzlePart::One, PuzzlePart::Two] {
+            let _ = solver.solve(puzzle_part);
+        }
+    }
+});
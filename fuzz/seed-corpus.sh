@@ -0,0 +1,14 @@
+#!/bin/sh
+# Seeds the corpus of every fuzz target with the fixtures of its day, e.g.
+# `data/example1_day05` goes to `corpus/day05/`, before running a target with
+# `cargo fuzz run day05`.
+set -eu
+
+cd "$(dirname "$0")"
+
+for fixture in ../data/*_day*; do
+    target="corpus/${fixture##*_}"
+
+    mkdir -p "$target"
+    cp "$fixture" "$target/"
+done
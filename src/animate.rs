@@ -0,0 +1,501 @@
+//! Replay of the simulation days in the terminal, drawn with ANSI escape codes from the events of the solvers.
+//!
+//! The player is controlled by lines read from the standard input:
+//! - `p` pauses or resumes the animation,
+//! - `s` or an empty line shows the next frame, pausing the animation if needed,
+//! - `q` skips the rest of the animation.
+
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use num_bigint::BigInt;
+use num_traits::Signed;
+
+use crate::solver::{PuzzlePart, Result};
+use crate::trace::{Event, Tracer};
+
+/// Days whose solvers emit events that can be animated.
+pub const ANIMATED_DAYS: &[u16] = &[5, 9, 10];
+
+/// Size of the viewport following the rope.
+const VIEWPORT_WIDTH: i64 = 60;
+const VIEWPORT_HEIGHT: i64 = 20;
+/// Distance to the viewport border from which the viewport scrolls to keep the head visible.
+const VIEWPORT_MARGIN: i64 = 3;
+
+const SCREEN_WIDTH: usize = 40;
+const SCREEN_HEIGHT: usize = 6;
+
+/// Moves the cursor to the top left corner, without clearing the screen to avoid flickering.
+const HOME: &str = "\x1b[H";
+const CLEAR_LINE: &str = "\x1b[K";
+const CLEAR_BELOW: &str = "\x1b[J";
+const REVERSE: &str = "\x1b[7m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Control {
+    TogglePause,
+    Step,
+    Quit,
+}
+
+impl Control {
+    pub fn from_line(line: &str) -> Option<Self> {
+        match line.trim() {
+            "p" => Some(Self::TogglePause),
+            "" | "s" => Some(Self::Step),
+            "q" => Some(Self::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the controls from the standard input in the background.
+fn stdin_controls() -> Receiver<Control> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+
+            if let Some(control) = Control::from_line(&line) {
+                if sender.send(control).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    receiver
+}
+
+/// Tracer drawing a frame for every event of a solver.
+pub struct Player<W: Write> {
+    writer: W,
+    frame_interval: Duration,
+    /// Started on first use, so that an input read from the standard input is read beforehand.
+    controls: Option<Receiver<Control>>,
+    paused: bool,
+    skipped: bool,
+    solving: Option<(u16, PuzzlePart)>,
+    /// Bottom left corner of the viewport following the rope.
+    viewport: (BigInt, BigInt),
+    trail: HashSet<(BigInt, BigInt)>,
+    screen: Vec<Vec<Option<bool>>>,
+    /// Lines of the frame being shown, drawn again when the animation is paused or resumed.
+    frame: Vec<String>,
+}
+
+impl<W: Write> Player<W> {
+    /// Creates a player drawing `frame_rate` frames per second.
+    pub fn new(writer: W, frame_rate: u32) -> Self {
+        Self {
+            writer,
+            frame_interval: Duration::from_secs(1) / frame_rate.max(1),
+            controls: None,
+            paused: false,
+            skipped: false,
+            solving: None,
+            viewport: Self::initial_viewport(),
+            trail: HashSet::default(),
+            screen: Self::blank_screen(),
+            frame: Vec::default(),
+        }
+    }
+
+    fn initial_viewport() -> (BigInt, BigInt) {
+        (BigInt::from(-VIEWPORT_WIDTH / 2), BigInt::from(-VIEWPORT_HEIGHT / 2))
+    }
+
+    fn blank_screen() -> Vec<Vec<Option<bool>>> {
+        vec![vec![None; SCREEN_WIDTH]; SCREEN_HEIGHT]
+    }
+
+    fn title(&self) -> String {
+        match self.solving {
+            Some((day, puzzle_part)) => format!("day {day} part {puzzle_part}"),
+            None => "animation".to_string(),
+        }
+    }
+
+    fn draw_stacks(&self, instruction: &str, stacks: &[String]) -> Vec<String> {
+        let mover = match self.solving {
+            Some((_, PuzzlePart::Two)) => "CrateMover 9001",
+            _ => "CrateMover 9000",
+        };
+        let height = stacks
+            .iter()
+            .map(|stack| stack.chars().count())
+            .max()
+            .unwrap_or_default();
+        let mut lines = vec![format!("{} - {mover}: {instruction}", self.title()), String::default()];
+
+        for level in (0..height).rev() {
+            lines.push(
+                stacks
+                    .iter()
+                    .map(|stack| stack.chars().nth(level).map_or("   ".to_string(), |c| format!("[{c}]")))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+
+        lines.push(
+            (1..=stacks.len())
+                .map(|i| format!(" {i} "))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+
+        lines
+    }
+
+    fn draw_rope(&mut self, knots: &[(BigInt, BigInt)]) -> Vec<String> {
+        let Some((head, tail)) = knots.first().zip(knots.last()) else {
+            return vec![self.title()];
+        };
+
+        self.trail.insert(tail.clone());
+
+        // scrolls the viewport when the head gets close to its border
+        let scroll = |position: &BigInt, origin: &mut BigInt, size: i64| {
+            let low = &*origin + VIEWPORT_MARGIN;
+            let high = &*origin + size - 1 - VIEWPORT_MARGIN;
+
+            if position < &low {
+                *origin += position - low;
+            } else if position > &high {
+                *origin += position - high;
+            }
+        };
+
+        scroll(&head.0, &mut self.viewport.0, VIEWPORT_WIDTH);
+        scroll(&head.1, &mut self.viewport.1, VIEWPORT_HEIGHT);
+
+        let (x0, y0) = &self.viewport;
+        let start = (BigInt::from(0), BigInt::from(0));
+        let mut lines = vec![
+            format!(
+                "{} - head at ({}, {}), {} position(s) visited by the tail",
+                self.title(),
+                head.0,
+                head.1,
+                self.trail.len()
+            ),
+            String::default(),
+        ];
+
+        for row in (0..VIEWPORT_HEIGHT).rev() {
+            let y = y0 + row;
+
+            lines.push(
+                (0..VIEWPORT_WIDTH)
+                    .map(|column| {
+                        let position = (x0 + column, y.clone());
+
+                        match knots.iter().position(|knot| *knot == position) {
+                            Some(0) => 'H',
+                            Some(i) if i == knots.len() - 1 => 'T',
+                            Some(i) => char::from_digit(i as u32 % 10, 10).unwrap(),
+                            None if self.trail.contains(&position) => '#',
+                            None if position == start => 's',
+                            None => '.',
+                        }
+                    })
+                    .collect(),
+            );
+        }
+
+        lines
+    }
+
+    fn draw_screen(&mut self, cycle: usize, x: &BigInt) -> Vec<String> {
+        let pixel = cycle
+            .checked_sub(1)
+            .filter(|pixel| *pixel < SCREEN_WIDTH * SCREEN_HEIGHT);
+
+        if let Some(pixel) = pixel {
+            let column = pixel % SCREEN_WIDTH;
+
+            self.screen[pixel / SCREEN_WIDTH][column] = Some((x - column).abs() <= BigInt::from(1));
+        }
+
+        let sprite = (0..SCREEN_WIDTH)
+            .map(|column| {
+                if (x - column).abs() <= BigInt::from(1) {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
+        let mut lines = vec![
+            format!("{} - cycle {cycle}, X = {x}", self.title()),
+            String::default(),
+            format!("sprite {sprite}"),
+            String::default(),
+        ];
+
+        for (row, pixels) in self.screen.iter().enumerate() {
+            let line = pixels
+                .iter()
+                .enumerate()
+                .map(|(column, lit)| {
+                    let c = match lit {
+                        Some(true) => '#',
+                        Some(false) => '.',
+                        None => ' ',
+                    };
+
+                    // highlights the pixel being drawn
+                    if pixel == Some(row * SCREEN_WIDTH + column) {
+                        format!("{REVERSE}{c}{RESET}")
+                    } else {
+                        c.to_string()
+                    }
+                })
+                .collect::<String>();
+
+            lines.push(format!("       {line}"));
+        }
+
+        lines
+    }
+
+    fn show(&mut self) -> Result<()> {
+        write!(self.writer, "{HOME}")?;
+
+        for line in self.frame.iter() {
+            writeln!(self.writer, "{line}{CLEAR_LINE}")?;
+        }
+
+        let state = if self.paused { "paused" } else { "playing" };
+
+        write!(
+            self.writer,
+            "\n[{state}] p: pause/resume, s or enter: step, q: skip the animation{CLEAR_LINE}\n{CLEAR_BELOW}"
+        )?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Waits for the next frame, following the controls read meanwhile.
+    fn wait(&mut self) -> Result<()> {
+        let controls = self.controls.take().unwrap_or_else(stdin_controls);
+        let result = self.follow_controls(&controls);
+
+        self.controls = Some(controls);
+
+        result
+    }
+
+    fn follow_controls(&mut self, controls: &Receiver<Control>) -> Result<()> {
+        let deadline = Instant::now() + self.frame_interval;
+
+        loop {
+            let control = if self.paused {
+                match controls.recv() {
+                    Ok(control) => control,
+                    // nothing can resume the animation anymore
+                    Err(_) => {
+                        self.paused = false;
+
+                        continue;
+                    }
+                }
+            } else {
+                match controls.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(control) => control,
+                    Err(RecvTimeoutError::Timeout) => return Ok(()),
+                    Err(RecvTimeoutError::Disconnected) => {
+                        thread::sleep(deadline.saturating_duration_since(Instant::now()));
+
+                        return Ok(());
+                    }
+                }
+            };
+
+            match control {
+                Control::TogglePause if self.paused => {
+                    self.paused = false;
+
+                    return self.show();
+                }
+                Control::Step if self.paused => return Ok(()),
+                Control::TogglePause | Control::Step => {
+                    self.paused = true;
+                    self.show()?;
+                }
+                Control::Quit => {
+                    self.skipped = true;
+
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+impl<W: Write> Tracer for Player<W> {
+    fn begin(&mut self, day: u16, puzzle_part: PuzzlePart) {
+        self.solving = Some((day, puzzle_part));
+        self.viewport = Self::initial_viewport();
+        self.trail.clear();
+        self.screen = Self::blank_screen();
+    }
+
+    fn enabled(&self) -> bool {
+        !self.skipped
+    }
+
+    fn trace(&mut self, event: Event) -> Result<()> {
+        if self.skipped {
+            return Ok(());
+        }
+
+        self.frame = match &event {
+            Event::Instruction { instruction, stacks } => self.draw_stacks(instruction, stacks),
+            Event::Step { knots } => {
+                let knots = knots.iter().map(|(x, y)| (x.to_big(), y.to_big())).collect::<Vec<_>>();
+
+                self.draw_rope(&knots)
+            }
+            Event::Cycle { cycle, x } => self.draw_screen(*cycle, &x.to_big()),
+            Event::Command { .. } => return Ok(()),
+        };
+
+        self.show()?;
+        self.wait()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::Integer;
+
+    /// Player drawing frames without waiting, with controls sent by the test instead of read from the terminal.
+    fn player() -> (Player<Vec<u8>>, mpsc::Sender<Control>) {
+        let (sender, receiver) = mpsc::channel();
+        let mut player = Player::new(Vec::default(), 1000);
+
+        player.controls = Some(receiver);
+
+        (player, sender)
+    }
+
+    #[test]
+    fn stacks_are_drawn_bottom_up() {
+        let (mut player, _controls) = player();
+
+        player.begin(5, PuzzlePart::Two);
+        player
+            .trace(Event::Instruction {
+                instruction: "move 1 from 2 to 1".to_string(),
+                stacks: vec!["ZND".to_string(), "MC".to_string(), "P".to_string()],
+            })
+            .unwrap();
+
+        assert_eq!(
+            player.frame,
+            [
+                "day 5 part two - CrateMover 9001: move 1 from 2 to 1",
+                "",
+                "[D]        ",
+                "[N] [C]    ",
+                "[Z] [M] [P]",
+                " 1   2   3 ",
+            ]
+        );
+
+        let output = String::from_utf8(player.writer).unwrap();
+
+        assert!(output.starts_with(HOME), "{output:?}");
+        assert!(output.contains(&format!("[Z] [M] [P]{CLEAR_LINE}\n")), "{output:?}");
+    }
+
+    #[test]
+    fn rope_frame_marks_knots_and_trail() {
+        let (mut player, _controls) = player();
+        let knot = |x: i64, y: i64| (Integer::from(x), Integer::from(y));
+
+        player.begin(9, PuzzlePart::One);
+
+        for knots in [vec![knot(1, 0), knot(0, 0)], vec![knot(2, 0), knot(1, 0)]] {
+            player.trace(Event::Step { knots }).unwrap();
+        }
+
+        assert_eq!(
+            player.frame[0],
+            "day 9 part one - head at (2, 0), 2 position(s) visited by the tail"
+        );
+
+        // the origin is at the center of the viewport
+        let row = &player.frame[2 + (VIEWPORT_HEIGHT / 2 - 1) as usize];
+        let origin = (VIEWPORT_WIDTH / 2) as usize;
+
+        assert_eq!(&row[origin..origin + 3], "#TH");
+    }
+
+    #[test]
+    fn screen_frame_lights_pixels_near_the_sprite() {
+        let (mut player, _controls) = player();
+
+        player.begin(10, PuzzlePart::Two);
+
+        for (cycle, x) in [(1, 1), (2, 1), (3, 16), (4, 16)] {
+            player
+                .trace(Event::Cycle {
+                    cycle,
+                    x: Integer::from(x),
+                })
+                .unwrap();
+        }
+
+        assert_eq!(player.frame[0], "day 10 part two - cycle 4, X = 16");
+        assert!(player.frame[2].starts_with("sprite ...............###..."));
+        assert!(player.frame[4].starts_with(&format!("       ##.{REVERSE}.{RESET} ")));
+    }
+
+    #[test]
+    fn quit_skips_the_rest_of_the_animation() {
+        let (mut player, controls) = player();
+
+        controls.send(Control::Quit).unwrap();
+        player.begin(10, PuzzlePart::One);
+        player
+            .trace(Event::Cycle {
+                cycle: 1,
+                x: Integer::from(1),
+            })
+            .unwrap();
+
+        assert!(!player.enabled());
+
+        let drawn = player.writer.len();
+
+        player
+            .trace(Event::Cycle {
+                cycle: 2,
+                x: Integer::from(1),
+            })
+            .unwrap();
+
+        assert_eq!(player.writer.len(), drawn);
+    }
+
+    #[test]
+    fn controls_are_read_from_lines() {
+        assert_eq!(Control::from_line("p\n"), Some(Control::TogglePause));
+        assert_eq!(Control::from_line(""), Some(Control::Step));
+        assert_eq!(Control::from_line(" q "), Some(Control::Quit));
+        assert_eq!(Control::from_line("x"), None);
+    }
+}
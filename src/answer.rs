@@ -0,0 +1,128 @@
+use std::fmt;
+
+use num_bigint::{BigInt, BigUint};
+use serde::{Serialize, Serializer};
+
+/// Solution of a puzzle part.
+///
+/// Integers compare by value whatever their representation, so that `Unsigned(42)` equals
+/// `BigSigned(42)`.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    BigUnsigned(BigUint),
+    BigSigned(BigInt),
+    Text(String),
+    /// Rows of lit (`true`) or dark (`false`) pixels.
+    Grid(Vec<Vec<bool>>),
+}
+
+impl Answer {
+    pub fn as_integer(&self) -> Option<BigInt> {
+        match self {
+            Self::Unsigned(n) => Some((*n).into()),
+            Self::Signed(n) => Some((*n).into()),
+            Self::BigUnsigned(n) => Some(n.clone().into()),
+            Self::BigSigned(n) => Some(n.clone()),
+            Self::Text(_) | Self::Grid(_) => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Text(a), Self::Text(b)) => a == b,
+            (Self::Grid(a), Self::Grid(b)) => a == b,
+            (a, b) => a.as_integer().is_some_and(|a| b.as_integer() == Some(a)),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsigned(n) => write!(f, "{n}"),
+            Self::Signed(n) => write!(f, "{n}"),
+            Self::BigUnsigned(n) => write!(f, "{n}"),
+            Self::BigSigned(n) => write!(f, "{n}"),
+            Self::Text(text) => write!(f, "{text}"),
+            Self::Grid(rows) => {
+                for (i, row) in rows.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+
+                    write!(
+                        f,
+                        "{}",
+                        row.iter().map(|lit| if *lit { '#' } else { '.' }).collect::<String>()
+                    )?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Machine integers are serialized as JSON numbers, big integers as strings of digits (JSON numbers being
+/// usually read as doubles) and grids as arrays of rows.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unsigned(n) => serializer.serialize_u64(*n),
+            Self::Signed(n) => serializer.serialize_i64(*n),
+            Self::BigUnsigned(_) | Self::BigSigned(_) | Self::Text(_) => serializer.collect_str(self),
+            Self::Grid(_) => serializer.collect_seq(self.to_string().lines()),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($variant:ident: $($ty:ty),+) => {
+        $(
+            impl From<$ty> for Answer {
+                fn from(n: $ty) -> Self {
+                    Self::$variant(n as _)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_integer!(Unsigned: u8, u16, u32, u64, usize);
+impl_from_integer!(Signed: i8, i16, i32, i64, isize);
+
+impl From<BigUint> for Answer {
+    fn from(n: BigUint) -> Self {
+        Self::BigUnsigned(n)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(n: BigInt) -> Self {
+        Self::BigSigned(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<Vec<Vec<bool>>> for Answer {
+    fn from(rows: Vec<Vec<bool>>) -> Self {
+        Self::Grid(rows)
+    }
+}
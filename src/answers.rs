@@ -0,0 +1,259 @@
+//! Manifest of the answers recorded for the puzzle inputs.
+//!
+//! Each entry starts with a `[{file} {day} {part}]` header, followed either by
+//! the answer lines, by a single `!{variant}` line naming the expected
+//! [`Error`] variant, or by a single `?` line for an answer still to be
+//! recorded. Entries are separated by an empty line.
+//!
+//! Answer lines which are empty or start with `!`, `?` or `\` are escaped by a
+//! leading `\`, so that any answer can be recorded.
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::{fmt, fs};
+
+use eyre::Context;
+
+use crate::answer::Answer;
+use crate::solver::{Error, PuzzlePart, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Answer(String),
+    /// Name of the [`Error`] variant, as given by [`Error::kind`].
+    Error(String),
+    /// Placeholder of a fixture not filled yet, e.g. by a scaffolded day, replaced when recording its answer.
+    Pending,
+}
+
+impl Outcome {
+    pub fn from_solution(solution: &Result<Answer>) -> Self {
+        match solution {
+            Ok(answer) => answer.into(),
+            Err(e) => Self::Error(e.kind().to_string()),
+        }
+    }
+
+    /// Renders a line by line diff from `self` (expected) to `other` (actual).
+    pub fn diff(&self, other: &Outcome) -> String {
+        let (expected, actual) = (self.to_string(), other.to_string());
+        let (expected, actual) = (expected.lines().collect::<Vec<_>>(), actual.lines().collect::<Vec<_>>());
+
+        (0..expected.len().max(actual.len()))
+            .flat_map(|i| {
+                match (expected.get(i), actual.get(i)) {
+                    (Some(a), Some(b)) if a == b => vec![format!("  {a}")],
+                    (a, b) => {
+                        a.map(|a| format!("- {a}"))
+                            .into_iter()
+                            .chain(b.map(|b| format!("+ {b}")))
+                            .collect()
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl From<&Answer> for Outcome {
+    fn from(answer: &Answer) -> Self {
+        // the manifest cannot represent trailing empty lines
+        Self::Answer(answer.to_string().trim_end_matches('\n').to_string())
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Answer(answer) => write!(f, "{answer}"),
+            Self::Error(variant) => write!(f, "!{variant}"),
+            Self::Pending => write!(f, "?"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    entries: BTreeMap<(String, u16, PuzzlePart), Outcome>,
+}
+
+impl Answers {
+    /// Loads the manifest at `path`, a missing manifest having no entry.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path).wrap_err_with(|| format!("Cannot read answers '{}'", path.display()))?;
+
+        content.parse()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_string()).wrap_err_with(|| format!("Cannot write answers '{}'", path.display()))?;
+
+        Ok(())
+    }
+
+    pub fn get(&self, file: &str, day: u16, puzzle_part: PuzzlePart) -> Option<&Outcome> {
+        self.entries.get(&(file.to_string(), day, puzzle_part))
+    }
+
+    /// Records `outcome` unless an outcome other than [`Outcome::Pending`] is already recorded, returning whether
+    /// it was inserted.
+    pub fn insert(&mut self, file: &str, day: u16, puzzle_part: PuzzlePart, outcome: Outcome) -> bool {
+        match self.entries.entry((file.to_string(), day, puzzle_part)) {
+            Entry::Vacant(entry) => {
+                entry.insert(outcome);
+
+                true
+            }
+            Entry::Occupied(mut entry) if *entry.get() == Outcome::Pending => {
+                entry.insert(outcome);
+
+                true
+            }
+            Entry::Occupied(_) => false,
+        }
+    }
+}
+
+impl FromStr for Answers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut entries = BTreeMap::default();
+        let mut lines = s.lines().peekable();
+
+        while let Some(line) = lines.next() {
+            if line.is_empty() {
+                continue;
+            }
+
+            let malformed = || {
+                Error::invalid_input(format!(
+                    "wrong answers entry: expected '[{{file}} {{day}} {{part}}]' (got '{line}')"
+                ))
+            };
+
+            let header = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+                .ok_or_else(malformed)?;
+
+            let key = match header.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [file, day, part] => {
+                    (
+                        file.to_string(),
+                        day.parse().map_err(|_| malformed())?,
+                        part.parse().map_err(|_| malformed())?,
+                    )
+                }
+                _ => return Err(malformed()),
+            };
+
+            let mut body = Vec::default();
+
+            while let Some(line) = lines.next_if(|line| !line.is_empty()) {
+                body.push(line);
+            }
+
+            let outcome = match body.as_slice() {
+                [line] if line.starts_with('!') => Outcome::Error(line[1..].to_string()),
+                ["?"] => Outcome::Pending,
+                _ => {
+                    Outcome::Answer(
+                        body.iter()
+                            .map(|line| line.strip_prefix('\\').unwrap_or(line))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    )
+                }
+            };
+
+            entries.insert(key, outcome);
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, ((file, day, puzzle_part), outcome)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            writeln!(f, "[{file} {day} {puzzle_part}]")?;
+
+            match outcome {
+                Outcome::Answer(answer) => {
+                    for line in answer.split('\n') {
+                        if line.is_empty() || line.starts_with(['!', '?', '\\']) {
+                            write!(f, "\\")?;
+                        }
+
+                        writeln!(f, "{line}")?;
+                    }
+                }
+                outcome => writeln!(f, "{outcome}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outcomes_round_trip() {
+        let outcomes = [
+            Outcome::Answer("24000".to_string()),
+            Outcome::Answer("#..#\n\n.##.".to_string()),
+            Outcome::Answer(String::default()),
+            Outcome::Answer("?".to_string()),
+            Outcome::Answer("!NoSolution".to_string()),
+            Outcome::Answer("\\n\n?\n!".to_string()),
+            Outcome::Error("NoSolution".to_string()),
+            Outcome::Pending,
+        ];
+        let mut answers = Answers::default();
+
+        for (day, outcome) in (1..).zip(&outcomes) {
+            answers.insert("input", day, PuzzlePart::One, outcome.clone());
+        }
+
+        let saved = answers.to_string();
+        let parsed = saved.parse::<Answers>().unwrap();
+
+        for (day, outcome) in (1..).zip(&outcomes) {
+            assert_eq!(
+                parsed.get("input", day, PuzzlePart::One),
+                Some(outcome),
+                "day {day} in\n{saved}"
+            );
+        }
+
+        assert_eq!(parsed.to_string(), saved);
+    }
+
+    #[test]
+    fn markers_are_escaped() {
+        let mut answers = Answers::default();
+
+        answers.insert("input", 1, PuzzlePart::One, Outcome::Answer("?".to_string()));
+        answers.insert("input", 1, PuzzlePart::Two, Outcome::Answer("!x\n\ny".to_string()));
+
+        assert_eq!(
+            answers.to_string(),
+            "[input 1 one]\n\\?\n\n[input 1 two]\n\\!x\n\\\ny\n"
+        );
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use std::{fmt, fs};
+
+use eyre::Context;
+use strum::{Display, EnumString};
+
+use crate::answer::Answer;
+use crate::solver::{Error, Input, Parameters, PuzzlePart, Result, Solve};
+use crate::trace::{NoTracer, Tracer};
+
+/// Outcome of a single parse + solve run.
+pub struct Sample {
+    pub solution: Result<Answer>,
+    /// `None` when streaming, parsing being part of solving.
+    pub parse_time: Option<Duration>,
+    /// `None` when parsing failed.
+    pub solve_time: Option<Duration>,
+}
+
+impl Sample {
+    pub fn measure(input: &Input, day: u16, parameters: &Parameters) -> Self {
+        let now = Instant::now();
+        let solver = input.parse(day);
+        let parse_time = Some(now.elapsed());
+
+        let (solution, solve_time) = match solver {
+            Ok(solver) => {
+                let now = Instant::now();
+                let solution = solver.solve_with(parameters, &mut NoTracer);
+
+                (solution, Some(now.elapsed()))
+            }
+            Err(e) => (Err(e), None),
+        };
+
+        Self {
+            solution,
+            parse_time,
+            solve_time,
+        }
+    }
+
+    /// Solves with `parameters` while reading the input, see [`Input::solve_streaming`], the parse time being part
+    /// of the solve time and not measured apart.
+    pub fn measure_streaming(input: &Input, day: u16, parameters: &Parameters) -> Self {
+        let now = Instant::now();
+        let solution = input.solve_streaming(day, parameters);
+
+        Self {
+            solution,
+            parse_time: None,
+            solve_time: Some(now.elapsed()),
+        }
+    }
+
+    /// Parses the input once and solves with each of `parameters` (usually the presets of the puzzle parts) using
+    /// the same solver, each sample reporting the shared parse time.
+    ///
+    /// The steps of the solver are reported to `tracer`, whose cost is included in the solve time.
+    pub fn measure_parts(input: &Input, day: u16, parameters: &[Parameters], tracer: &mut dyn Tracer) -> Vec<Self> {
+        let now = Instant::now();
+        let solver = input.parse(day);
+        let parse_time = Some(now.elapsed());
+
+        match solver {
+            Ok(solver) => {
+                parameters
+                    .iter()
+                    .map(|parameters| {
+                        tracer.begin(day, parameters.puzzle_part());
+
+                        let now = Instant::now();
+                        let solution = solver.solve_with(parameters, tracer);
+
+                        Self {
+                            solution,
+                            parse_time,
+                            solve_time: Some(now.elapsed()),
+                        }
+                    })
+                    .collect()
+            }
+            Err(e) => {
+                // errors cannot be cloned: the first part owns the parse error, the other ones parse again
+                let mut error = Some(e);
+
+                parameters
+                    .iter()
+                    .map(|parameters| {
+                        match error.take() {
+                            Some(e) => {
+                                Self {
+                                    solution: Err(e),
+                                    parse_time,
+                                    solve_time: None,
+                                }
+                            }
+                            None => Self::measure(input, day, parameters),
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Phase {
+    Parse,
+    Solve,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort();
+
+        // nearest-rank percentile
+        let percentile = |p: usize| samples[(samples.len() * p).div_ceil(100).max(1) - 1];
+
+        Some(Self {
+            min: samples[0],
+            median: percentile(50),
+            mean: samples.iter().sum::<Duration>() / samples.len() as u32,
+            p95: percentile(95),
+        })
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:.2?}, median {:.2?}, mean {:.2?}, p95 {:.2?}",
+            self.min, self.median, self.mean, self.p95
+        )
+    }
+}
+
+pub struct Report {
+    pub day: u16,
+    pub puzzle_part: PuzzlePart,
+    pub iterations: usize,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+impl Report {
+    /// Runs `warmup` unmeasured iterations then `iterations` measured ones.
+    pub fn run(input: &Input, day: u16, puzzle_part: PuzzlePart, warmup: usize, iterations: usize) -> Result<Self> {
+        if iterations == 0 {
+            return Err(Error::invalid_input("bench requires at least one iteration"));
+        }
+
+        let parameters = Parameters::preset(puzzle_part);
+
+        for _ in 0..warmup {
+            Sample::measure(input, day, &parameters).solution?;
+        }
+
+        let (parse_times, solve_times) = (0..iterations)
+            .map(|_| {
+                let sample = Sample::measure(input, day, &parameters);
+
+                sample.solution?;
+
+                // a successful solution implies that parsing succeeded, samples not being streamed
+                Ok((
+                    sample.parse_time.unwrap_or_default(),
+                    sample.solve_time.unwrap_or_default(),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+
+        Ok(Self {
+            day,
+            puzzle_part,
+            iterations,
+            parse: Stats::from_samples(parse_times).unwrap(),
+            solve: Stats::from_samples(solve_times).unwrap(),
+        })
+    }
+
+    pub fn stats(&self, phase: Phase) -> &Stats {
+        match phase {
+            Phase::Parse => &self.parse,
+            Phase::Solve => &self.solve,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Regression {
+    pub day: u16,
+    pub puzzle_part: PuzzlePart,
+    pub phase: Phase,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} part {} {}: median {:.2?} -> {:.2?} (+{:.1}%)",
+            self.day,
+            self.puzzle_part,
+            self.phase,
+            self.baseline,
+            self.current,
+            (self.current.as_secs_f64() / self.baseline.as_secs_f64() - 1.0) * 100.0
+        )
+    }
+}
+
+/// Bench statistics saved between runs, one line per day, part and phase:
+/// `{day} {part} {phase} {min} {median} {mean} {p95}` with durations in nanoseconds.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    entries: BTreeMap<(u16, PuzzlePart, Phase), Stats>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            fs::read_to_string(path).wrap_err_with(|| format!("Cannot read baseline '{}'", path.display()))?;
+
+        let entries = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let malformed = || Error::invalid_input(format!("wrong baseline entry '{line}'"));
+
+                match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                    [day, part, phase, durations @ ..] if durations.len() == 4 => {
+                        let durations = durations
+                            .iter()
+                            .map(|nanos| nanos.parse().map(Duration::from_nanos).map_err(|_| malformed()))
+                            .collect::<Result<Vec<_>>>()?;
+
+                        Ok((
+                            (
+                                day.parse().map_err(|_| malformed())?,
+                                part.parse().map_err(|_| malformed())?,
+                                phase.parse().map_err(|_| malformed())?,
+                            ),
+                            Stats {
+                                min: durations[0],
+                                median: durations[1],
+                                mean: durations[2],
+                                p95: durations[3],
+                            },
+                        ))
+                    }
+                    _ => Err(malformed()),
+                }
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { entries })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = self
+            .entries
+            .iter()
+            .map(|((day, puzzle_part, phase), stats)| {
+                format!(
+                    "{day} {puzzle_part} {phase} {} {} {} {}\n",
+                    stats.min.as_nanos(),
+                    stats.median.as_nanos(),
+                    stats.mean.as_nanos(),
+                    stats.p95.as_nanos()
+                )
+            })
+            .collect::<String>();
+
+        fs::write(path, content).wrap_err_with(|| format!("Cannot write baseline '{}'", path.display()))?;
+
+        Ok(())
+    }
+
+    pub fn insert(&mut self, report: &Report) {
+        for phase in [Phase::Parse, Phase::Solve] {
+            self.entries
+                .insert((report.day, report.puzzle_part, phase), *report.stats(phase));
+        }
+    }
+
+    /// Lists the phases whose median got slower than the baseline by more than `threshold` percent.
+    pub fn regressions(&self, report: &Report, threshold: f64) -> Vec<Regression> {
+        [Phase::Parse, Phase::Solve]
+            .into_iter()
+            .filter_map(|phase| {
+                let baseline = self.entries.get(&(report.day, report.puzzle_part, phase))?.median;
+                let current = report.stats(phase).median;
+
+                // no slowdown can be measured relatively to a phase too fast to be timed
+                if baseline.is_zero() {
+                    return None;
+                }
+
+                (current.as_secs_f64() > baseline.as_secs_f64() * (1.0 + threshold / 100.0)).then_some(Regression {
+                    day: report.day,
+                    puzzle_part: report.puzzle_part,
+                    phase,
+                    baseline,
+                    current,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    fn stats(median: u64) -> Stats {
+        Stats {
+            min: Duration::from_millis(median),
+            median: Duration::from_millis(median),
+            mean: Duration::from_millis(median),
+            p95: Duration::from_millis(median),
+        }
+    }
+
+    fn report(parse: u64, solve: u64) -> Report {
+        Report {
+            day: 1,
+            puzzle_part: PuzzlePart::One,
+            iterations: 1,
+            parse: stats(parse),
+            solve: stats(solve),
+        }
+    }
+
+    #[test]
+    fn percentiles_are_nearest_ranks() {
+        let cases: &[(&[u64], [u64; 4])] = &[
+            (&[7], [7, 7, 7, 7]),
+            (&[2, 1], [1, 1, 1, 2]),
+            (&[5, 1, 4, 2, 3], [1, 3, 3, 5]),
+            (&[4, 1, 3, 2], [1, 2, 2, 4]),
+        ];
+
+        for (samples, [min, median, mean, p95]) in cases {
+            let stats = Stats::from_samples(millis(samples)).unwrap();
+
+            assert_eq!(stats.min, Duration::from_millis(*min), "{samples:?}");
+            assert_eq!(stats.median, Duration::from_millis(*median), "{samples:?}");
+            assert_eq!(stats.p95, Duration::from_millis(*p95), "{samples:?}");
+
+            // the mean of an even number of samples may fall between two milliseconds
+            assert_eq!(stats.mean.as_millis() as u64, *mean, "{samples:?}");
+        }
+
+        assert_eq!(Stats::from_samples(Vec::default()), None);
+    }
+
+    #[test]
+    fn mean_is_exact() {
+        let stats = Stats::from_samples(millis(&[1, 2, 3, 4])).unwrap();
+
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+    }
+
+    #[test]
+    fn regressions_exceed_the_threshold() {
+        let mut baseline = Baseline::default();
+
+        baseline.insert(&report(10, 100));
+
+        let regressions = baseline.regressions(&report(11, 130), 20.0);
+
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].phase, Phase::Solve);
+        assert_eq!(
+            regressions[0].to_string(),
+            "day 1 part one solve: median 100.00ms -> 130.00ms (+30.0%)"
+        );
+    }
+
+    #[test]
+    fn zero_median_baseline_is_not_a_regression() {
+        let mut baseline = Baseline::default();
+
+        baseline.insert(&report(0, 0));
+
+        assert!(baseline.regressions(&report(5, 5), 20.0).is_empty());
+    }
+
+    #[test]
+    fn missing_baseline_entry_is_not_a_regression() {
+        let baseline = Baseline::default();
+
+        assert!(baseline.regressions(&report(5, 5), 20.0).is_empty());
+    }
+}
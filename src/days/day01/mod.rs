@@ -1,28 +1,71 @@
-use std::io::{self, Read};
+//! Day 1: Calorie Counting.
+//!
+//! The input lists the calories of the foods carried by each [`Elf`], elves being separated by blank lines.
+//!
+//! ```
+//! use advent_of_code_2022::days::day01::Solver;
+//!
+//! let solver = Solver::from_reader("1000\n2000\n\n4000\n".as_bytes())?;
+//! let calories = solver.elves().iter().map(|elf| elf.total_calories()).collect::<Vec<_>>();
+//!
+//! assert_eq!(calories, [3000u32.into(), 4000u32.into()]);
+//! # Ok::<(), advent_of_code_2022::solver::Error>(())
+//! ```
+
+use std::io::{self, BufRead, Read, Write};
 
 use itertools::Itertools;
-use num_bigint::BigUint;
-use num_traits::identities::Zero;
+use pest::error::ErrorVariant;
 use pest::Parser;
 use pest_derive::Parser;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 
-use crate::solver::{Error, PuzzlePart, Result, Solve};
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::number::Natural;
+use crate::solver::{Error, Parameter, ParameterKind, Parameters, Puzzle, PuzzlePart, Records, Result, Solve};
+use crate::stream::LineReader;
+use crate::trace::Tracer;
 
+/// Elf carrying foods, each one worth some calories.
 #[derive(Debug, Default)]
 pub struct Elf {
-    foods: Vec<BigUint>,
+    foods: Vec<Natural>,
 }
 
 impl Elf {
-    pub fn new(foods: Vec<BigUint>) -> Self {
+    pub fn new(foods: Vec<Natural>) -> Self {
         Self { foods }
     }
 
-    pub fn total_calories(&self) -> BigUint {
-        self.foods.iter().fold(BigUint::zero(), |acc, value| acc + value)
+    pub fn foods(&self) -> &[Natural] {
+        &self.foods
+    }
+
+    pub fn total_calories(&self) -> Natural {
+        self.foods.iter().sum()
     }
 }
 
+const TOP: Parameter = Parameter {
+    name: "top",
+    description: "number of elves carrying the most calories to sum",
+    defaults: ["1", "3"],
+    kind: ParameterKind::Unsigned { min: 1, max: u64::MAX },
+};
+
+pub const PUZZLE: Puzzle = Puzzle {
+    day: 1,
+    title: "Calorie Counting",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[TOP],
+    queries: &[],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: Some(|reader, parameters| Solver::solve_streaming(reader, parameters)),
+    generator: &Generator,
+};
+
 #[derive(Parser)]
 #[grammar = "days/day01/grammar.pest"]
 pub struct Solver {
@@ -31,10 +74,21 @@ pub struct Solver {
 
 impl Solver {
     pub fn from_reader(reader: impl Read) -> Result<Self> {
+        Self::from_reader_with(reader, &mut Records::default())
+    }
+
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with(reader: impl Read, records: &mut Records) -> Result<Self> {
         let input = io::read_to_string(reader)?;
 
         let tokens = Self::parse(Rule::Input, &input)
-            .map_err(|e| Error::InvalidInput(e.to_string()))?
+            .map_err(|e| {
+                records.grammar_error(&input, e, |line| {
+                    Self::parse(Rule::Line, line)?;
+
+                    Ok(())
+                })
+            })?
             .next()
             .unwrap();
 
@@ -42,28 +96,118 @@ impl Solver {
             .into_inner()
             .filter_map(|token| {
                 if let Rule::Elf = token.as_rule() {
-                    Some(Elf::new(
-                        token.into_inner().map(|v| v.as_str().parse().unwrap()).collect(),
-                    ))
+                    Some(
+                        token
+                            .into_inner()
+                            .map(|v| {
+                                v.as_str().parse().map_err(|e| {
+                                    let variant = ErrorVariant::<Rule>::CustomError {
+                                        message: format!("wrong calories: {e} (got '{}')", v.as_str()),
+                                    };
+
+                                    pest::error::Error::new_from_span(variant, v.as_span()).into()
+                                })
+                            })
+                            .collect::<Result<_>>()
+                            .map(Elf::new),
+                    )
                 } else {
                     None
                 }
             })
-            .collect();
+            .collect::<Result<_>>()?;
 
         Ok(Self { elves })
     }
+
+    pub fn elves(&self) -> &[Elf] {
+        &self.elves
+    }
+
+    /// Solves while reading the input, keeping only the calories of the current elf and of the best ones.
+    pub fn solve_streaming(reader: impl BufRead, parameters: &Parameters) -> Result<Answer> {
+        let top = parameters.get::<usize>(&TOP)?;
+        let mut lines = LineReader::new(reader);
+        // calories carried by the best elves so far, from the most to the least
+        let mut best: Vec<Natural> = Vec::default();
+        let mut current: Option<Natural> = None;
+        let mut terminated = true;
+
+        let mut keep = |calories: Natural| {
+            let i = best.partition_point(|other| *other >= calories);
+
+            best.insert(i, calories);
+            best.truncate(top);
+        };
+
+        while let Some(line) = lines.next_line()? {
+            terminated = line.terminated;
+
+            if line.content.is_empty() {
+                let calories = current.take().ok_or_else(|| {
+                    Error::invalid_input("wrong elf: expected calories (got an empty line)").on_line(line.number, "")
+                })?;
+
+                keep(calories);
+            } else if line.content.starts_with('0') || !line.content.bytes().all(|c| c.is_ascii_digit()) {
+                return Err(Error::invalid_input(format!(
+                    "wrong calories: expected a number without leading zero (got '{}')",
+                    line.content
+                ))
+                .on_line(line.number, line.content));
+            } else {
+                current = Some(current.unwrap_or_default() + line.content.parse::<Natural>()?);
+            }
+        }
+
+        if !terminated {
+            return Err(Error::invalid_input("missing newline at the end of the input"));
+        }
+
+        if let Some(calories) = current {
+            keep(calories);
+        }
+
+        // rejected by the grammar of the in-memory mode, rather than being empty
+        if best.is_empty() {
+            return Err(Error::invalid_input("wrong input: expected at least one elf"));
+        }
+
+        Ok(best.into_iter().sum::<Natural>().into())
+    }
 }
 
 impl Solve for Solver {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String> {
-        let calories = self.elves.iter().map(Elf::total_calories).collect::<Vec<_>>();
+    fn solve_with(&self, parameters: &Parameters, _tracer: &mut dyn Tracer) -> Result<Answer> {
+        let top = parameters.get::<usize>(&TOP)?;
 
-        let solution = match puzzle_part {
-            PuzzlePart::One => calories.into_iter().max().unwrap_or_default().to_string(),
-            PuzzlePart::Two => calories.into_iter().sorted().rev().take(3).sum::<BigUint>().to_string(),
-        };
+        Ok(self
+            .elves
+            .iter()
+            .map(Elf::total_calories)
+            .sorted()
+            .rev()
+            .take(top)
+            .sum::<Natural>()
+            .into())
+    }
+}
+
+/// Generates `size` elves, at least one since an input without elf is invalid, carrying 1 to 10 foods each.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        for i in 0..size.max(1) {
+            if i > 0 {
+                writeln!(writer)?;
+            }
+
+            for _ in 0..rng.gen_range(1..=10) {
+                writeln!(writer, "{}", rng.gen_range(1..=99_999u32))?;
+            }
+        }
 
-        Ok(solution)
+        Ok(())
     }
 }
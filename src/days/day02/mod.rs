@@ -1,9 +1,33 @@
-use std::io::{self, Read};
+//! Day 2: Rock Paper Scissors.
+//!
+//! Each [`Round`] pairs the [`Play`] of the opponent with the [`Action`] of the strategy guide, read either as
+//! a play (part one) or as the expected [`RoundResult`] (part two).
+//!
+//! ```
+//! use advent_of_code_2022::days::day02::{Play, RoundResult, Solver};
+//!
+//! let solver = Solver::from_reader("A Y\nB X\n".as_bytes())?;
+//! let round = &solver.rounds()[0];
+//!
+//! assert_eq!(round.opponent(), Play::Rock);
+//! assert_eq!(round.action().play(), Play::Paper);
+//! assert_eq!(round.action().round_result(), RoundResult::Draw);
+//! assert!(Play::Rock.is_weakness_of(Play::Scissors));
+//! # Ok::<(), advent_of_code_2022::solver::Error>(())
+//! ```
+
+use std::io::{self, BufRead, Read, Write};
 
 use pest::Parser;
 use pest_derive::Parser;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
 
-use crate::solver::{Error, PuzzlePart, Result, Solve};
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::solver::{Error, Parameters, Puzzle, PuzzlePart, Records, Result, Solve};
+use crate::stream::LineReader;
+use crate::trace::Tracer;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(u8)]
@@ -14,6 +38,7 @@ pub enum Play {
 }
 
 impl Play {
+    /// Whether this play beats `other`.
     pub fn is_weakness_of(&self, other: Self) -> bool {
         matches!(
             (self, other),
@@ -21,6 +46,7 @@ impl Play {
         )
     }
 
+    /// Play beating this one.
     pub fn get_weakness(&self) -> Self {
         match self {
             Self::Rock => Self::Paper,
@@ -29,6 +55,7 @@ impl Play {
         }
     }
 
+    /// Play beaten by this one.
     pub fn get_resistance(&self) -> Self {
         match self {
             Self::Rock => Self::Scissors,
@@ -46,6 +73,7 @@ pub enum RoundResult {
     Won = 6,
 }
 
+/// Second column of the strategy guide.
 pub struct Action {
     play: Play,
     round_result: RoundResult,
@@ -55,10 +83,74 @@ impl Action {
     pub fn new(play: Play, round_result: RoundResult) -> Self {
         Self { play, round_result }
     }
+
+    pub fn play(&self) -> Play {
+        self.play
+    }
+
+    pub fn round_result(&self) -> RoundResult {
+        self.round_result
+    }
 }
 
 pub struct Round(Play, Action);
 
+impl Round {
+    /// Decodes a line of the strategy guide, `None` for unknown letters.
+    fn from_letters(play: &str, action: &str) -> Option<Self> {
+        let play = match play {
+            "A" => Play::Rock,
+            "B" => Play::Paper,
+            "C" => Play::Scissors,
+            _ => return None,
+        };
+        let action = match action {
+            "X" => Action::new(Play::Rock, RoundResult::Lost),
+            "Y" => Action::new(Play::Paper, RoundResult::Draw),
+            "Z" => Action::new(Play::Scissors, RoundResult::Won),
+            _ => return None,
+        };
+
+        Some(Self(play, action))
+    }
+
+    pub fn opponent(&self) -> Play {
+        self.0
+    }
+
+    pub fn action(&self) -> &Action {
+        &self.1
+    }
+
+    /// Plays of the opponent and ours, the action being read as our play (part one) or as the round result
+    /// (part two).
+    fn plays(&self, puzzle_part: PuzzlePart) -> (Play, Play) {
+        let play = match puzzle_part {
+            PuzzlePart::One => self.1.play,
+            PuzzlePart::Two => {
+                match self.1.round_result {
+                    RoundResult::Lost => self.0.get_resistance(),
+                    RoundResult::Draw => self.0,
+                    RoundResult::Won => self.0.get_weakness(),
+                }
+            }
+        };
+
+        (self.0, play)
+    }
+}
+
+pub const PUZZLE: Puzzle = Puzzle {
+    day: 2,
+    title: "Rock Paper Scissors",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[],
+    queries: &[],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: Some(|reader, parameters| Solver::solve_streaming(reader, parameters.puzzle_part())),
+    generator: &Generator,
+};
+
 #[derive(Parser)]
 #[grammar = "days/day02/grammar.pest"]
 pub struct Solver {
@@ -67,10 +159,21 @@ pub struct Solver {
 
 impl Solver {
     pub fn from_reader(reader: impl Read) -> Result<Self> {
+        Self::from_reader_with(reader, &mut Records::default())
+    }
+
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with(reader: impl Read, records: &mut Records) -> Result<Self> {
         let input = io::read_to_string(reader)?;
 
         let tokens = Self::parse(Rule::Input, &input)
-            .map_err(|e| Error::InvalidInput(e.to_string()))?
+            .map_err(|e| {
+                records.grammar_error(&input, e, |line| {
+                    Self::parse(Rule::Line, line)?;
+
+                    Ok(())
+                })
+            })?
             .next()
             .unwrap();
 
@@ -80,20 +183,8 @@ impl Solver {
                 if let Rule::Round = token.as_rule() {
                     let mut token = token.into_inner();
 
-                    Some(Round(
-                        match token.next().unwrap().as_str() {
-                            "A" => Play::Rock,
-                            "B" => Play::Paper,
-                            "C" => Play::Scissors,
-                            _ => unreachable!(),
-                        },
-                        match token.next().unwrap().as_str() {
-                            "X" => Action::new(Play::Rock, RoundResult::Lost),
-                            "Y" => Action::new(Play::Paper, RoundResult::Draw),
-                            "Z" => Action::new(Play::Scissors, RoundResult::Won),
-                            _ => unreachable!(),
-                        },
-                    ))
+                    // letters are checked by the grammar
+                    Some(Round::from_letters(token.next().unwrap().as_str(), token.next().unwrap().as_str()).unwrap())
                 } else {
                     None
                 }
@@ -102,40 +193,62 @@ impl Solver {
 
         Ok(Self { rounds })
     }
-}
 
-impl Solve for Solver {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String> {
-        match puzzle_part {
-            PuzzlePart::One => {
-                Ok(self
-                    .rounds
-                    .iter()
-                    .map(|round| (round.0, round.1.play))
-                    .fold(0u32, compute_round)
-                    .to_string())
-            }
-            PuzzlePart::Two => {
-                Ok(self
-                    .rounds
-                    .iter()
-                    .map(|round| {
-                        let play = match round.1.round_result {
-                            RoundResult::Lost => round.0.get_resistance(),
-                            RoundResult::Draw => round.0,
-                            RoundResult::Won => round.0.get_weakness(),
-                        };
-
-                        (round.0, play)
-                    })
-                    .fold(0u32, compute_round)
-                    .to_string())
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    /// Solves while reading the input, scoring each round as soon as it is read.
+    pub fn solve_streaming(reader: impl BufRead, puzzle_part: PuzzlePart) -> Result<Answer> {
+        let mut lines = LineReader::new(reader);
+        let mut score = 0u64;
+        let mut rounds = 0usize;
+
+        while let Some(line) = lines.next_line()? {
+            let round = line
+                .content
+                .split_once(' ')
+                .and_then(|(play, action)| Round::from_letters(play, action))
+                .ok_or_else(|| {
+                    Error::invalid_input(format!(
+                        "wrong round: expected 'A', 'B' or 'C' then 'X', 'Y' or 'Z' (got '{}')",
+                        line.content
+                    ))
+                    .on_line(line.number, line.content)
+                })?;
+
+            if !line.terminated {
+                return Err(Error::invalid_input("missing newline at the end of the input"));
             }
+
+            score = compute_round(score, round.plays(puzzle_part));
+            rounds += 1;
         }
+
+        // rejected by the grammar of the in-memory mode, rather than being empty
+        if rounds == 0 {
+            return Err(Error::invalid_input("wrong input: expected at least one round"));
+        }
+
+        Ok(score.into())
     }
 }
 
-fn compute_round(acc: u32, (a, b): (Play, Play)) -> u32 {
+impl Solve for Solver {
+    fn solve_with(&self, parameters: &Parameters, _tracer: &mut dyn Tracer) -> Result<Answer> {
+        let puzzle_part = parameters.puzzle_part();
+
+        Ok(self
+            .rounds
+            .iter()
+            .map(|round| round.plays(puzzle_part))
+            .fold(0u64, compute_round)
+            .into())
+    }
+}
+
+/// Adds the score of a round to `acc`, on 64 bits since 32 ones overflow after about 480M rounds.
+fn compute_round(acc: u64, (a, b): (Play, Play)) -> u64 {
     let round_result = if a.is_weakness_of(b) {
         RoundResult::Lost
     } else if b.is_weakness_of(a) {
@@ -144,5 +257,21 @@ fn compute_round(acc: u32, (a, b): (Play, Play)) -> u32 {
         RoundResult::Draw
     };
 
-    acc + round_result as u32 + b as u32
+    acc + round_result as u64 + b as u64
+}
+
+/// Generates `size` rounds, at least one since an input without round is invalid.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        for _ in 0..size.max(1) {
+            let play = *b"ABC".choose(rng).unwrap() as char;
+            let action = *b"XYZ".choose(rng).unwrap() as char;
+
+            writeln!(writer, "{play} {action}")?;
+        }
+
+        Ok(())
+    }
 }
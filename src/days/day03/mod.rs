@@ -1,25 +1,59 @@
+//! Day 3: Rucksack Reorganization.
+//!
+//! Each [`Rucksack`] holds [`Item`]s split into two compartments, elves being grouped by three.
+//!
+//! ```
+//! use advent_of_code_2022::days::day03::{Item, Solver};
+//!
+//! let solver = Solver::from_reader("vJrwpWtwJgWrhcsFMMfFFhFp\nabcA\nAdef\n".as_bytes())?;
+//! let misplaced_item = solver.rucksacks()[0].find_misplaced_item();
+//!
+//! assert_eq!(misplaced_item, Some(Item::try_from('p')?));
+//! assert_eq!(Item::try_from('p')?.get_priority(), 16);
+//! # Ok::<(), advent_of_code_2022::solver::Error>(())
+//! ```
+
 use std::collections::HashSet;
 use std::fmt;
-use std::io::{self, Read};
+use std::io::{self, BufRead, Read, Write};
 
 use pest::Parser;
 use pest_derive::Parser;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 
-use crate::solver::{Error, PuzzlePart, Result, Solve};
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::solver::{Error, Parameters, Puzzle, PuzzlePart, Records, Result, Solve};
+use crate::stream::LineReader;
+use crate::trace::Tracer;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Item(u8);
 
 impl Item {
+    /// Priority from 1 for `a` to 26 for `z`, then from 27 for `A` to 52 for `Z`.
     pub fn get_priority(&self) -> usize {
-        match self.0 as char {
-            'a'..='z' => (self.0 - b'a' + 1) as usize,
-            'A'..='Z' => (self.0 - b'A' + 27) as usize,
-            _ => unreachable!(),
+        // items are ASCII letters, see `Item::try_from`
+        if self.0.is_ascii_lowercase() {
+            (self.0 - b'a' + 1) as usize
+        } else {
+            (self.0 - b'A' + 27) as usize
         }
     }
 }
 
+impl TryFrom<char> for Item {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self> {
+        c.is_ascii_alphabetic()
+            .then_some(Self(c as u8))
+            .ok_or_else(|| Error::invalid_input(format!("wrong item: expected ASCII letter (got '{c}')")))
+    }
+}
+
 pub struct Rucksack {
     items: Vec<Item>,
     compartments: [HashSet<Item>; 2],
@@ -36,6 +70,18 @@ impl fmt::Display for Rucksack {
 }
 
 impl Rucksack {
+    /// Splits `items` into two compartments of the same size.
+    pub fn new(items: Vec<Item>) -> Self {
+        let pivot = items.len() / 2;
+        let compartments = [
+            items[0..pivot].iter().copied().collect(),
+            items[pivot..].iter().copied().collect(),
+        ];
+
+        Self { items, compartments }
+    }
+
+    /// Item found in both compartments.
     pub fn find_misplaced_item(&self) -> Option<Item> {
         (&self.compartments[0] & &self.compartments[1]).into_iter().next()
     }
@@ -45,6 +91,17 @@ impl Rucksack {
     }
 }
 
+pub const PUZZLE: Puzzle = Puzzle {
+    day: 3,
+    title: "Rucksack Reorganization",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[],
+    queries: &[],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: Some(|reader, parameters| Solver::solve_streaming(reader, parameters.puzzle_part())),
+    generator: &Generator,
+};
+
 #[derive(Parser)]
 #[grammar = "days/day03/grammar.pest"]
 pub struct Solver {
@@ -53,10 +110,21 @@ pub struct Solver {
 
 impl Solver {
     pub fn from_reader(reader: impl Read) -> Result<Self> {
+        Self::from_reader_with(reader, &mut Records::default())
+    }
+
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with(reader: impl Read, records: &mut Records) -> Result<Self> {
         let input = io::read_to_string(reader)?;
 
         let tokens = Self::parse(Rule::Input, &input)
-            .map_err(|e| Error::InvalidInput(e.to_string()))?
+            .map_err(|e| {
+                records.grammar_error(&input, e, |line| {
+                    Self::parse(Rule::Line, line)?;
+
+                    Ok(())
+                })
+            })?
             .next()
             .unwrap();
 
@@ -66,60 +134,144 @@ impl Solver {
                 if let Rule::Rucksack = token.as_rule() {
                     let items = token
                         .into_inner()
-                        .map(|v| Item(v.as_str().chars().next().unwrap() as u8))
-                        .collect::<Vec<Item>>();
+                        .flat_map(|v| v.as_str().chars())
+                        .map(Item::try_from)
+                        .collect::<Result<Vec<Item>>>();
 
-                    let pivot = items.len() / 2;
-                    let compartments = [
-                        items[0..pivot].iter().copied().collect(),
-                        items[pivot..].iter().copied().collect(),
-                    ];
-
-                    Some(Rucksack { items, compartments })
+                    Some(items.map(Rucksack::new))
                 } else {
                     None
                 }
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>>>()?;
 
         if rucksacks.len() % 3 != 0 {
-            Err(Error::InvalidInput(
-                "each elf group must strictly contain 3 members".to_string(),
-            ))
+            Err(Error::invalid_input("each elf group must strictly contain 3 members"))
         } else {
             Ok(Self { rucksacks })
         }
     }
+
+    pub fn rucksacks(&self) -> &[Rucksack] {
+        &self.rucksacks
+    }
+
+    /// Solves while reading the input, keeping only the rucksacks of the current elf group.
+    pub fn solve_streaming(reader: impl BufRead, puzzle_part: PuzzlePart) -> Result<Answer> {
+        let mut lines = LineReader::new(reader);
+        let mut group = Vec::with_capacity(3);
+        let mut priorities = 0;
+        let mut rucksacks = 0usize;
+
+        while let Some(line) = lines.next_line()? {
+            if line.content.is_empty() || line.content.len() % 2 != 0 || !line.terminated {
+                return Err(Error::invalid_input(format!(
+                    "wrong rucksack: expected an even number of items then a newline (got '{}')",
+                    line.content
+                ))
+                .on_line(line.number, line.content));
+            }
+
+            let items = line
+                .content
+                .chars()
+                .map(Item::try_from)
+                .collect::<Result<Vec<_>>>()
+                .map_err(|e| e.on_line(line.number, line.content))?;
+
+            group.push(Rucksack::new(items));
+            rucksacks += 1;
+
+            if group.len() == 3 {
+                priorities += match puzzle_part {
+                    PuzzlePart::One => misplaced_priorities(&group)?,
+                    PuzzlePart::Two => badge_priorities(&group),
+                };
+                group.clear();
+            }
+        }
+
+        // rejected by the grammar of the in-memory mode, rather than being empty
+        if rucksacks == 0 {
+            Err(Error::invalid_input("wrong input: expected at least one rucksack"))
+        } else if !group.is_empty() {
+            Err(Error::invalid_input("each elf group must strictly contain 3 members"))
+        } else {
+            Ok(priorities.into())
+        }
+    }
+}
+
+fn misplaced_priorities(rucksacks: &[Rucksack]) -> Result<usize> {
+    let items = rucksacks
+        .iter()
+        .map(|r| {
+            r.find_misplaced_item()
+                .ok_or_else(|| Error::NoSolution(format!("none misplaced item found in '{r}'")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(items.into_iter().map(|item| item.get_priority()).sum())
+}
+
+fn badge_priorities(rucksacks: &[Rucksack]) -> usize {
+    rucksacks
+        .chunks_exact(3)
+        .flat_map(|x| &(&x[0].get_all_items() & &x[1].get_all_items()) & &x[2].get_all_items())
+        .map(|item| item.get_priority())
+        .sum()
 }
 
 impl Solve for Solver {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String> {
+    fn solve_with(&self, parameters: &Parameters, _tracer: &mut dyn Tracer) -> Result<Answer> {
+        let puzzle_part = parameters.puzzle_part();
+
         match puzzle_part {
-            PuzzlePart::One => {
-                let items = self
-                    .rucksacks
+            PuzzlePart::One => Ok(misplaced_priorities(&self.rucksacks)?.into()),
+            PuzzlePart::Two => Ok(badge_priorities(&self.rucksacks).into()),
+        }
+    }
+}
+
+/// Generates `size` rucksacks, rounded up to complete elf groups of which there is at least one.
+///
+/// The compartments of a rucksack share exactly one item and the rucksacks of a group share at least one badge.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        let letters = (b'a'..=b'z').chain(b'A'..=b'Z').collect::<Vec<_>>();
+
+        for _ in 0..size.div_ceil(3).max(1) {
+            let badge = *letters.choose(rng).unwrap();
+
+            for _ in 0..3 {
+                let misplaced = *letters.choose(rng).unwrap();
+                let mut others = letters
                     .iter()
-                    .map(|r| {
-                        r.find_misplaced_item()
-                            .ok_or_else(|| Error::NoSolution(format!("none misplaced item found in '{r}'")))
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-
-                Ok(items
-                    .into_iter()
-                    .map(|item| item.get_priority())
-                    .sum::<usize>()
-                    .to_string())
-            }
-            PuzzlePart::Two => {
-                Ok(self
-                    .rucksacks
-                    .chunks_exact(3)
-                    .flat_map(|x| &(&x[0].get_all_items() & &x[1].get_all_items()) & &x[2].get_all_items())
-                    .map(|item| item.get_priority())
-                    .sum::<usize>()
-                    .to_string())
+                    .copied()
+                    .filter(|item| *item != misplaced && *item != badge)
+                    .collect::<Vec<_>>();
+
+                others.shuffle(rng);
+
+                // compartments draw their other items from disjoint pools
+                let (first_pool, second_pool) = others.split_at(others.len() / 2);
+                let length = rng.gen_range(2..=24);
+
+                let mut first = vec![misplaced, badge];
+                first.extend((2..length).map(|_| *first_pool.choose(rng).unwrap()));
+                first.shuffle(rng);
+
+                let mut second = vec![misplaced];
+                second.extend((1..length).map(|_| *second_pool.choose(rng).unwrap()));
+                second.shuffle(rng);
+
+                first.extend(second);
+                writeln!(writer, "{}", String::from_utf8_lossy(&first))?;
             }
         }
+
+        Ok(())
     }
 }
@@ -1,11 +1,30 @@
-use std::io::{BufRead, BufReader, Read};
+//! Day 4: Camp Cleanup.
+//!
+//! Each [`PeerCleaning`] pairs the [`Section`]s assigned to two elves.
+//!
+//! ```
+//! use advent_of_code_2022::days::day04::PeerCleaning;
+//!
+//! let peer_cleaning = "2-8,3-7".parse::<PeerCleaning>()?;
+//!
+//! assert!(peer_cleaning.is_fully_overlapping());
+//! assert!(peer_cleaning.is_overlapping());
+//! # Ok::<(), advent_of_code_2022::solver::Error>(())
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 
-use eyre::Context;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 
-use crate::solver::{Error, PuzzlePart, Result, Solve};
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::solver::{Error, Parameters, Puzzle, PuzzlePart, Records, Result, Solve};
+use crate::trace::Tracer;
 
+/// Inclusive range of section IDs.
 #[derive(Debug, Clone)]
 pub struct Section(RangeInclusive<usize>);
 
@@ -25,18 +44,22 @@ impl FromStr for Section {
     fn from_str(s: &str) -> Result<Self> {
         match s.split_once('-') {
             Some((start, end)) => {
-                let start = start
-                    .parse()
-                    .wrap_err_with(|| format!("start section must be a valid unsigned integer (got '{start}')"))?;
-
-                let end = end
-                    .parse()
-                    .wrap_err_with(|| format!("end section must be a valid unsigned integer (got '{end}')"))?;
+                let start = start.parse().map_err(|_| {
+                    Error::invalid_input(format!(
+                        "start section must be a valid unsigned integer (got '{start}')"
+                    ))
+                    .within(s, start)
+                })?;
+
+                let end = end.parse().map_err(|_| {
+                    Error::invalid_input(format!("end section must be a valid unsigned integer (got '{end}')"))
+                        .within(s, end)
+                })?;
 
                 Ok(Self(start..=end))
             }
             None => {
-                Err(Error::InvalidInput(format!(
+                Err(Error::invalid_input(format!(
                     "wrong section: expected '{{0-9}}+-{{0-9}}+' (got '{s}')"
                 )))
             }
@@ -55,6 +78,14 @@ impl PeerCleaning {
     pub fn is_overlapping(&self) -> bool {
         self.0.overlap(&self.1) || self.1.overlap(&self.0)
     }
+
+    /// Whether the pair counts towards the answer: fully overlapping (part one) or overlapping (part two).
+    fn counts(&self, puzzle_part: PuzzlePart) -> bool {
+        match puzzle_part {
+            PuzzlePart::One => self.is_fully_overlapping(),
+            PuzzlePart::Two => self.is_overlapping(),
+        }
+    }
 }
 
 impl FromStr for PeerCleaning {
@@ -64,14 +95,12 @@ impl FromStr for PeerCleaning {
         match s.split_once(',') {
             Some((a, b)) => {
                 Ok(Self(
-                    a.parse()
-                        .wrap_err_with(|| Error::InvalidInput(format!("peer cleaning '{s}' is malformed")))?,
-                    b.parse()
-                        .wrap_err_with(|| Error::InvalidInput(format!("peer cleaning '{s}' is malformed")))?,
+                    a.parse().map_err(|e: Error| e.within(s, a))?,
+                    b.parse().map_err(|e: Error| e.within(s, b))?,
                 ))
             }
             None => {
-                Err(Error::InvalidInput(format!(
+                Err(Error::invalid_input(format!(
                     "wrong peer cleaning: expected '{{0-9}}+-{{0-9}},{{0-9}}+-{{0-9}}+' (got '{s}')"
                 )))
             }
@@ -79,41 +108,84 @@ impl FromStr for PeerCleaning {
     }
 }
 
+pub const PUZZLE: Puzzle = Puzzle {
+    day: 4,
+    title: "Camp Cleanup",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[],
+    queries: &[],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: Some(|reader, parameters| Solver::solve_streaming(reader, parameters.puzzle_part())),
+    generator: &Generator,
+};
+
 pub struct Solver {
     peer_cleanings: Vec<PeerCleaning>,
 }
 
 impl Solver {
     pub fn from_reader<R: Read>(reader: BufReader<R>) -> Result<Self> {
-        let peer_cleanings = reader
-            .lines()
-            .into_iter()
-            .map(|line| line?.parse())
-            .collect::<Result<Vec<_>>>()?;
+        Self::from_reader_with(reader, &mut Records::default())
+    }
+
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with<R: Read>(reader: BufReader<R>, records: &mut Records) -> Result<Self> {
+        let peer_cleanings = records.collect::<_, Vec<_>>(reader.lines().enumerate().map(|(i, line)| {
+            let line = line?;
+
+            line.parse().map_err(|e: Error| e.on_line(i + 1, &line))
+        }))?;
+
+        records.finish()?;
 
         Ok(Self { peer_cleanings })
     }
+
+    pub fn peer_cleanings(&self) -> &[PeerCleaning] {
+        &self.peer_cleanings
+    }
+
+    /// Solves while reading the input, counting each pair as soon as it is read.
+    pub fn solve_streaming(reader: impl BufRead, puzzle_part: PuzzlePart) -> Result<Answer> {
+        let mut count = 0usize;
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let peer_cleaning = line.parse::<PeerCleaning>().map_err(|e| e.on_line(i + 1, &line))?;
+
+            if peer_cleaning.counts(puzzle_part) {
+                count += 1;
+            }
+        }
+
+        Ok(count.into())
+    }
 }
 
 impl Solve for Solver {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String> {
-        match puzzle_part {
-            PuzzlePart::One => {
-                Ok(self
-                    .peer_cleanings
-                    .iter()
-                    .filter(|peer_cleaning| peer_cleaning.is_fully_overlapping())
-                    .count()
-                    .to_string())
-            }
-            PuzzlePart::Two => {
-                Ok(self
-                    .peer_cleanings
-                    .iter()
-                    .filter(|peer_cleaning| peer_cleaning.is_overlapping())
-                    .count()
-                    .to_string())
-            }
+    fn solve_with(&self, parameters: &Parameters, _tracer: &mut dyn Tracer) -> Result<Answer> {
+        let puzzle_part = parameters.puzzle_part();
+
+        Ok(self
+            .peer_cleanings
+            .iter()
+            .filter(|peer_cleaning| peer_cleaning.counts(puzzle_part))
+            .count()
+            .into())
+    }
+}
+
+/// Generates `size` pairs of sections.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        for _ in 0..size {
+            let [a, b, c, d] = [(); 4].map(|_| rng.gen_range(1..=99u32));
+
+            writeln!(writer, "{}-{},{}-{}", a.min(b), a.max(b), c.min(d), c.max(d))?;
         }
+
+        Ok(())
     }
 }
@@ -1,16 +1,48 @@
+//! Day 5: Supply Stacks.
+//!
+//! The input draws stacks of crates, then lists the [`Instruction`]s moving them with a [`CrateMover`].
+//!
+//! ```
+//! use std::io::BufReader;
+//!
+//! use advent_of_code_2022::days::day05::{CrateMover9000, CrateMover9001, Solver};
+//! use advent_of_code_2022::trace::NoTracer;
+//!
+//! let input = "[A]    \n[B] [C]\n 1   2 \n\nmove 2 from 1 to 2\n";
+//! let solver = Solver::from_reader(BufReader::new(input.as_bytes()))?;
+//!
+//! assert_eq!(solver.instructions()[0].to_string(), "move 2 from 1 to 2");
+//!
+//! let stacks = solver.move_crates(CrateMover9000, &mut NoTracer)?;
+//! assert_eq!(stacks[&1].iter().collect::<String>(), "CAB");
+//!
+//! let stacks = solver.move_first_crates(0, CrateMover9001, &mut NoTracer)?;
+//! assert_eq!(stacks[&0].iter().collect::<String>(), "BA");
+//! # Ok::<(), advent_of_code_2022::solver::Error>(())
+//! ```
+
 use std::collections::{HashMap, VecDeque};
-use std::io::{BufRead, BufReader, Read};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::str::FromStr;
 
-use eyre::Context;
 use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 
-use crate::solver::{Error, PuzzlePart, Result, Solve};
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::solver::{Error, Parameter, ParameterKind, Parameters, Puzzle, PuzzlePart, Query, Records, Result, Solve};
+use crate::trace::{Event, NoTracer, Tracer};
 
+/// Crane model, taking crates from the top of a stack.
 pub trait CrateMover {
+    /// Removes `quantity` crates from `stack`, returned in the order they are put on another stack.
     fn grab_crates(&self, stack: &mut VecDeque<char>, quantity: usize) -> VecDeque<char>;
 }
 
+/// Crane moving crates one at a time.
 pub struct CrateMover9000;
 
 impl CrateMover for CrateMover9000 {
@@ -19,6 +51,7 @@ impl CrateMover for CrateMover9000 {
     }
 }
 
+/// Crane moving several crates at once.
 pub struct CrateMover9001;
 
 impl CrateMover for CrateMover9001 {
@@ -27,6 +60,7 @@ impl CrateMover for CrateMover9001 {
     }
 }
 
+/// Move of crates between two stacks, referenced by their label.
 #[derive(Debug)]
 pub struct Instruction {
     quantity: usize,
@@ -34,6 +68,30 @@ pub struct Instruction {
     to_stack: usize,
 }
 
+impl Instruction {
+    pub fn quantity(&self) -> usize {
+        self.quantity
+    }
+
+    pub fn from_stack(&self) -> usize {
+        self.from_stack
+    }
+
+    pub fn to_stack(&self) -> usize {
+        self.to_stack
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.quantity, self.from_stack, self.to_stack
+        )
+    }
+}
+
 impl FromStr for Instruction {
     type Err = Error;
 
@@ -43,7 +101,7 @@ impl FromStr for Instruction {
         let values = s.split_whitespace().collect::<Vec<_>>();
 
         if values.len() != 6 {
-            return Err(Error::InvalidInput(format!(
+            return Err(Error::invalid_input(format!(
                 "wrong instruction: expected 'move {{0-9}}+ from {{0-9}}+ to {{0-9}}+' (got '{s}')"
             )));
         }
@@ -51,18 +109,20 @@ impl FromStr for Instruction {
         let values = values
             .into_iter()
             .enumerate()
-            .filter_map(|(i, s)| {
+            .filter_map(|(i, value)| {
                 match TOKENS[i] {
-                    Some(token) if token != s => {
-                        Some(Err(Error::InvalidInput(format!(
-                            "wrong instruction: expected token '{token}' (got '{s}')"
-                        ))))
+                    Some(token) if token != value => {
+                        Some(Err(Error::invalid_input(format!(
+                            "wrong instruction: expected token '{token}' (got '{value}')"
+                        ))
+                        .within(s, value)))
                     }
-                    None => Some(s.parse::<usize>()
-                                    .wrap_err_with(||
-                                       Error::InvalidInput(format!("wrong instruction: quantity or stack indice must be a valid unsigned integer value (got '{s}')"))
-                                    )
-                                    .map_err(Into::into)),
+                    None => Some(value.parse::<usize>().map_err(|_| {
+                        Error::invalid_input(format!(
+                            "wrong instruction: quantity or stack indice must be a valid unsigned integer value (got '{value}')"
+                        ))
+                        .within(s, value)
+                    })),
                     _ => None,
                 }
             })
@@ -76,6 +136,30 @@ impl FromStr for Instruction {
     }
 }
 
+const RUN: Query = Query {
+    name: "run",
+    arguments: "k crate-mover",
+    description: "stacks after the first k instructions applied by the crate mover 9000 or 9001",
+};
+
+const CRATE_MOVER: Parameter = Parameter {
+    name: "crate-mover",
+    description: "model of the crane moving the crates",
+    defaults: ["9000", "9001"],
+    kind: ParameterKind::Choice(&["9000", "9001"]),
+};
+
+pub const PUZZLE: Puzzle = Puzzle {
+    day: 5,
+    title: "Supply Stacks",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[CRATE_MOVER],
+    queries: &[RUN],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: None,
+    generator: &Generator,
+};
+
 pub struct Solver {
     stacks: HashMap<usize, VecDeque<char>>,
     indices: HashMap<usize, usize>,
@@ -84,14 +168,20 @@ pub struct Solver {
 
 impl Solver {
     pub fn from_reader<R: Read>(reader: BufReader<R>) -> Result<Self> {
+        Self::from_reader_with(reader, &mut Records::default())
+    }
+
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with<R: Read>(reader: BufReader<R>, records: &mut Records) -> Result<Self> {
         let mut is_stack = true;
 
         let (stacks, instructions): (Vec<_>, Vec<_>) = reader
             .lines()
-            .map(|line| Ok(line?))
+            .enumerate()
+            .map(|(i, line)| Ok((i + 1, line?)))
             .collect::<Result<Vec<_>>>()?
             .into_iter()
-            .partition(|line| {
+            .partition(|(_, line)| {
                 is_stack &= !line.is_empty();
 
                 is_stack
@@ -111,57 +201,67 @@ impl Solver {
             }
         };
 
-        let indices = stacks[stacks.len() - 1]
-            .split(splitter(4))
-            .enumerate()
-            .map(|(i, s)| {
+        let ((indices_line, indices), stacks) = stacks
+            .split_last()
+            .ok_or_else(|| Error::invalid_input("missing stacks before the instructions"))?;
+        let indices =
+            records.collect::<_, HashMap<usize, usize>>(indices.split(splitter(4)).enumerate().map(|(i, s)| {
                 match s.as_bytes() {
                     [b' ', key @ b'0'..=b'9', b' '] => Ok(((*key - b'0') as usize, i)),
                     [b' ', key @ b'0'..=b'9'] => Ok(((*key - b'0') as usize, i)),
                     _ => {
-                        Err(Error::InvalidInput(format!(
-                            "wrong indice: expected ' [0-9] ' (got '{s}')"
-                        )))
+                        Err(
+                            Error::invalid_input(format!("wrong indice: expected ' [0-9] ' (got '{s}')"))
+                                .within(indices, s)
+                                .on_line(*indices_line, indices),
+                        )
                     }
                 }
-            })
-            .collect::<Result<HashMap<usize, usize>>>()?;
+            }))?;
 
-        let stacks = stacks[..stacks.len() - 1]
+        let stacks = stacks
             .iter()
-            .flat_map(|line| {
+            .flat_map(|(line_number, line)| {
                 line.split(splitter(4))
                     .enumerate()
                     .filter_map(|(i, s)| {
                         match s.as_bytes() {
-                            [b'[', c, b']'] => Some((i, Some(*c as char))),
-                            [b' ', b' ', b' '] => Some((i, None)),
+                            [b'[', c, b']'] => Some((i, Some(*c as char), *line_number, line, s)),
+                            [b' ', b' ', b' '] => Some((i, None, *line_number, line, s)),
                             _ => None,
                         }
                     })
                     .collect::<Vec<_>>()
             })
-            .fold(Ok(HashMap::<usize, VecDeque<char>>::default()), |stacks, (key, c)| {
-                let mut stacks = stacks?;
-                let stack = stacks.entry(key).or_default();
-
-                if let Some(c) = c {
-                    stack.push_front(c);
-                } else if !stack.is_empty() {
-                    return Err(Error::InvalidInput(
-                        "wrong stack: floating crate(s) detected".to_string(),
-                    ));
-                }
+            .fold(
+                Ok::<_, Error>(HashMap::<usize, VecDeque<char>>::default()),
+                |stacks, (key, c, line_number, line, s)| {
+                    let mut stacks = stacks?;
+                    let stack = stacks.entry(key).or_default();
+
+                    if let Some(c) = c {
+                        stack.push_front(c);
+                    } else if !stack.is_empty() {
+                        records.report(
+                            Error::invalid_input("wrong stack: floating crate(s) detected")
+                                .within(line, s)
+                                .on_line(line_number, line),
+                        )?;
+                    }
 
-                Ok(stacks)
-            })?;
+                    Ok(stacks)
+                },
+            )?;
 
-        let instructions = instructions
-            .into_iter()
-            // skips empty separator line
-            .filter(|line| !line.is_empty())
-            .map(|line| line.parse())
-            .collect::<Result<Vec<_>>>()?;
+        let instructions = records.collect::<_, Vec<_>>(
+            instructions
+                .into_iter()
+                // skips empty separator line
+                .filter(|(_, line)| !line.is_empty())
+                .map(|(i, line)| line.parse().map_err(|e: Error| e.on_line(i, &line))),
+        )?;
+
+        records.finish()?;
 
         Ok(Self {
             stacks,
@@ -170,10 +270,29 @@ impl Solver {
         })
     }
 
-    pub fn move_crates(&self, mover: impl CrateMover) -> Result<HashMap<usize, VecDeque<char>>> {
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Applies every instruction, returning the stacks from bottom to top keyed by their column, from 0.
+    pub fn move_crates(
+        &self,
+        mover: impl CrateMover,
+        tracer: &mut dyn Tracer,
+    ) -> Result<HashMap<usize, VecDeque<char>>> {
+        self.move_first_crates(self.instructions.len(), mover, tracer)
+    }
+
+    /// Applies only the first `count` instructions.
+    pub fn move_first_crates(
+        &self,
+        count: usize,
+        mover: impl CrateMover,
+        tracer: &mut dyn Tracer,
+    ) -> Result<HashMap<usize, VecDeque<char>>> {
         let mut stacks = self.stacks.clone();
 
-        for instruction in self.instructions.iter() {
+        for instruction in self.instructions.iter().take(count) {
             let stack = self
                 .indices
                 .get(&instruction.from_stack)
@@ -202,6 +321,17 @@ impl Solver {
                 })?;
 
             stack.append(&mut crates);
+
+            if tracer.enabled() {
+                tracer.trace(Event::Instruction {
+                    instruction: instruction.to_string(),
+                    stacks: stacks
+                        .iter()
+                        .sorted_by_key(|(key, _)| **key)
+                        .map(|(_, crates)| crates.iter().collect())
+                        .collect(),
+                })?;
+            }
         }
 
         Ok(stacks)
@@ -209,16 +339,111 @@ impl Solver {
 }
 
 impl Solve for Solver {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String> {
-        let stacks = match puzzle_part {
-            PuzzlePart::One => self.move_crates(CrateMover9000),
-            PuzzlePart::Two => self.move_crates(CrateMover9001),
+    fn solve_with(&self, parameters: &Parameters, tracer: &mut dyn Tracer) -> Result<Answer> {
+        let stacks = match parameters.get(&CRATE_MOVER)? {
+            9000 => self.move_crates(CrateMover9000, tracer),
+            9001 => self.move_crates(CrateMover9001, tracer),
+            model => {
+                return Err(Error::invalid_input(format!(
+                    "crate-mover must be 9000 or 9001 (got '{model}')"
+                )))
+            }
         }?;
 
         Ok(stacks
             .into_iter()
             .sorted_by_key(|(key, _)| *key)
             .filter_map(|(_, crates)| crates.back().copied())
-            .collect())
+            .collect::<String>()
+            .into())
+    }
+
+    fn query(&self, name: &str, arguments: &[&str]) -> Result<Answer> {
+        match name {
+            "run" => {
+                let count = RUN.argument(arguments, 0, "an unsigned integer")?;
+                let stacks = match RUN.argument(arguments, 1, "9000 or 9001")? {
+                    9000 => self.move_first_crates(count, CrateMover9000, &mut NoTracer),
+                    9001 => self.move_first_crates(count, CrateMover9001, &mut NoTracer),
+                    model => {
+                        return Err(Error::invalid_input(format!(
+                            "run: crate-mover must be 9000 or 9001 (got '{model}')"
+                        )))
+                    }
+                }?;
+
+                // one line per stack, from its bottom crate to its top one
+                Ok(self
+                    .indices
+                    .iter()
+                    .sorted_by_key(|(_, column)| **column)
+                    .map(|(key, column)| {
+                        format!(
+                            "{key} {}",
+                            stacks
+                                .get(column)
+                                .map(|crates| crates.iter().collect::<String>())
+                                .unwrap_or_default()
+                        )
+                    })
+                    .join("\n")
+                    .into())
+            }
+            _ => Err(Error::invalid_input(format!("unknown query '{name}'"))),
+        }
+    }
+}
+
+/// Generates 9 stacks of crates followed by `size` moves, each move taking crates from a non-empty stack.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        let mut stacks = (0..9)
+            .map(|_| {
+                (0..rng.gen_range(0..=8))
+                    .map(|_| rng.gen_range(b'A'..=b'Z') as char)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // moves need at least one crate
+        if stacks.iter().all(|stack| stack.is_empty()) {
+            stacks[0].push('A');
+        }
+
+        let height = stacks.iter().map(Vec::len).max().unwrap_or_default();
+
+        for level in (0..height).rev() {
+            let line = stacks
+                .iter()
+                .map(|stack| {
+                    stack
+                        .get(level)
+                        .map(|c| format!("[{c}]"))
+                        .unwrap_or_else(|| "   ".to_string())
+                })
+                .join(" ");
+
+            writeln!(writer, "{line}")?;
+        }
+
+        writeln!(writer, "{}", (1..=stacks.len()).map(|i| format!(" {i} ")).join(" "))?;
+        writeln!(writer)?;
+
+        for _ in 0..size {
+            let candidates = (0..stacks.len()).filter(|i| !stacks[*i].is_empty()).collect::<Vec<_>>();
+            let from = *candidates.choose(rng).unwrap();
+            let to = (from + rng.gen_range(1..stacks.len())) % stacks.len();
+            let height = stacks[from].len();
+            let quantity = rng.gen_range(1..=height);
+
+            let crates = stacks[from].split_off(height - quantity);
+            stacks[to].extend(crates);
+
+            writeln!(writer, "move {quantity} from {} to {}", from + 1, to + 1)?;
+        }
+
+        Ok(())
     }
 }
@@ -1,13 +1,59 @@
+//! Day 6: Tuning Trouble.
+//!
+//! The input is a single signal whose markers are the first windows of distinct characters.
+//!
+//! ```
+//! use std::io::BufReader;
+//!
+//! use advent_of_code_2022::days::day06::Solver;
+//!
+//! let solver = Solver::from_reader(BufReader::new("mjqjpqmgbljsphdztnvjfqwrcgsmlb".as_bytes()))?;
+//!
+//! assert_eq!(solver.start_packet(4), Some(7));
+//! assert_eq!(solver.start_packet(14), Some(19));
+//! # Ok::<(), advent_of_code_2022::solver::Error>(())
+//! ```
+
 use std::collections::HashSet;
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufRead, BufReader, Read, Write};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::solver::{Error, Parameter, ParameterKind, Parameters, Puzzle, PuzzlePart, Result, Solve};
+use crate::trace::Tracer;
+
+const WINDOW: Parameter = Parameter {
+    name: "window",
+    description: "number of distinct characters marking the start of a packet",
+    defaults: ["4", "14"],
+    kind: ParameterKind::Unsigned { min: 1, max: u64::MAX },
+};
 
-use crate::solver::{Error, PuzzlePart, Result, Solve};
+pub const PUZZLE: Puzzle = Puzzle {
+    day: 6,
+    title: "Tuning Trouble",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[WINDOW],
+    queries: &[],
+    parse: |reader, _records| Ok(Solver::from_reader(reader)?.into()),
+    stream: Some(|reader, parameters| Solver::solve_streaming(reader, parameters)),
+    generator: &Generator,
+};
 
 pub struct Solver {
     signal: Vec<u8>,
 }
 
 impl Solver {
+    pub fn signal(&self) -> &[u8] {
+        &self.signal
+    }
+
+    /// Number of characters read until the end of the first window of `n` distinct characters.
     pub fn start_packet(&self, n: usize) -> Option<usize> {
         self
             .signal
@@ -24,38 +70,190 @@ impl Solver {
 }
 
 impl Solver {
-    pub fn from_reader<R: Read>(reader: BufReader<R>) -> Result<Self> {
-        let mut signals = reader
-            .lines()
-            .map(|line| Ok(line?.chars().map(|c| c as u8).collect::<Vec<_>>()))
-            .collect::<Result<Vec<_>>>()?;
-
-        match signals.len() {
-            0 => Err(Error::EmptyInput),
-            1 => {
-                Ok(Self {
-                    signal: signals.pop().unwrap(),
-                })
+    pub fn from_reader<R: Read>(mut reader: BufReader<R>) -> Result<Self> {
+        // lines of bytes, so that markers are found among the same bytes as in the streaming mode
+        let mut signals = Vec::default();
+        let mut signal = Vec::default();
+
+        while reader.read_until(b'\n', &mut signal)? > 0 {
+            if signal.ends_with(b"\n") {
+                signal.pop();
+
+                if signal.ends_with(b"\r") {
+                    signal.pop();
+                }
+            }
+
+            signals.push(std::mem::take(&mut signal));
+        }
+
+        match signals.as_slice() {
+            [] => Err(Error::EmptyInput),
+            [signal] => Ok(Self { signal: signal.clone() }),
+            [_, signal, ..] => {
+                Err(
+                    Error::invalid_input(format!("expected only one signal (got {} signals)", signals.len()))
+                        .on_line(2, &String::from_utf8_lossy(signal)),
+                )
+            }
+        }
+    }
+}
+
+impl Solver {
+    /// Solves while reading the input one byte at a time, remembering only where each byte was last seen.
+    pub fn solve_streaming(reader: impl BufRead, parameters: &Parameters) -> Result<Answer> {
+        let mut scanner = MarkerScanner::new(parameters.get(&WINDOW)?);
+        let mut bytes = reader.bytes();
+        let mut empty = true;
+        // a carriage return is part of the signal unless it ends the line
+        let mut carriage_return = false;
+        let mut line_ended = false;
+
+        while let Some(c) = bytes.next().transpose()? {
+            empty = false;
+
+            if std::mem::take(&mut carriage_return) {
+                if c == b'\n' {
+                    line_ended = true;
+
+                    break;
+                }
+
+                scanner.push(b'\r');
+            }
+
+            match c {
+                b'\n' => {
+                    line_ended = true;
+
+                    break;
+                }
+                b'\r' => carriage_return = true,
+                c => scanner.push(c),
+            }
+        }
+
+        if carriage_return {
+            scanner.push(b'\r');
+        }
+
+        if empty {
+            return Err(Error::EmptyInput);
+        }
+
+        if line_ended {
+            // the other signals are counted without being kept, except the start of the second one to locate the error
+            let mut second_signal = Vec::default();
+            let mut line_feeds = 0;
+            let mut unterminated = false;
+
+            for c in bytes {
+                match c? {
+                    b'\n' => {
+                        line_feeds += 1;
+                        unterminated = false;
+                    }
+                    c => {
+                        if line_feeds == 0 && second_signal.len() < DIAGNOSTIC_LENGTH {
+                            second_signal.push(c);
+                        }
+
+                        unterminated = true;
+                    }
+                }
             }
-            _ => {
-                Err(Error::InvalidInput(format!(
-                    "expected only one signal (got {} signals)",
-                    signals.len()
-                )))
+
+            let signals = line_feeds + usize::from(unterminated);
+
+            if signals > 0 {
+                if line_feeds > 0 && second_signal.ends_with(b"\r") {
+                    second_signal.pop();
+                }
+
+                return Err(
+                    Error::invalid_input(format!("expected only one signal (got {} signals)", signals + 1))
+                        .on_line(2, &String::from_utf8_lossy(&second_signal)),
+                );
             }
         }
+
+        let n = scanner.n;
+
+        scanner
+            .marker
+            .ok_or_else(|| Error::NoSolution(format!("no consecutive '{n}' distinct characters in signal")))
+            .map(Answer::from)
+    }
+}
+
+/// Maximum number of bytes of an extra signal shown by the error of the streaming mode.
+const DIAGNOSTIC_LENGTH: usize = 80;
+
+/// Finds the first window of `n` distinct bytes of a signal read one byte at a time.
+struct MarkerScanner {
+    n: usize,
+    read: usize,
+    /// Start of the window of distinct bytes ending at the last byte read.
+    start: usize,
+    /// Number of bytes read until the last occurrence of each byte, 0 if not seen yet.
+    seen: [usize; 256],
+    marker: Option<usize>,
+}
+
+impl MarkerScanner {
+    fn new(n: usize) -> Self {
+        Self {
+            n,
+            read: 0,
+            start: 0,
+            seen: [0; 256],
+            marker: None,
+        }
+    }
+
+    fn push(&mut self, c: u8) {
+        if self.marker.is_some() {
+            return;
+        }
+
+        self.read += 1;
+
+        let seen = &mut self.seen[c as usize];
+
+        self.start = self.start.max(*seen);
+        *seen = self.read;
+
+        if self.read - self.start >= self.n {
+            self.marker = Some(self.read);
+        }
     }
 }
 
 impl Solve for Solver {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String> {
-        let n = match puzzle_part {
-            PuzzlePart::One => 4,
-            PuzzlePart::Two => 14,
-        };
+    fn solve_with(&self, parameters: &Parameters, _tracer: &mut dyn Tracer) -> Result<Answer> {
+        let n = parameters.get(&WINDOW)?;
 
         self.start_packet(n)
             .ok_or_else(|| Error::NoSolution(format!("no consecutive '{n}' distinct characters in signal")))
-            .map(|value| value.to_string())
+            .map(Answer::from)
+    }
+}
+
+/// Generates a signal of `size` characters drawn from 12 letters, followed by 14 distinct letters so that both
+/// markers exist.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        let mut signal = (0..size).map(|_| rng.gen_range(b'a'..=b'l')).collect::<Vec<_>>();
+        let mut marker = (b'a'..=b'z').collect::<Vec<_>>();
+
+        marker.shuffle(rng);
+        signal.extend(&marker[..14]);
+
+        writeln!(writer, "{}", String::from_utf8_lossy(&signal))?;
+
+        Ok(())
     }
 }
@@ -1,14 +1,16 @@
-use eyre::Context as _;
-use num_bigint::BigUint;
+use std::fmt;
 
 use super::file_system::{Context, Node, NodeLike};
+use crate::number::Natural;
 use crate::solver::{Error, Result};
 
-pub trait Command: std::fmt::Debug {
+/// Shell command of the transcript, with the outputs printed below it.
+pub trait Command: fmt::Debug + fmt::Display {
     fn execute(&self, context: &mut Context) -> Result<()>;
     fn add_output(&mut self, output: &str) -> Result<()>;
 }
 
+/// Parser of the command lines, without their leading `$`.
 pub struct CommandFactory;
 
 impl CommandFactory {
@@ -18,13 +20,9 @@ impl CommandFactory {
         match tokens.as_slice() {
             ["cd", target] => Ok(Box::new(ChangeDirectory::new(target.to_string()))),
             ["ls"] => Ok(Box::<ListDirectory>::default()),
-            ["cd"] => {
-                Err(Error::InvalidInput(
-                    "wrong cd command: missing target directory".to_string(),
-                ))
-            }
-            [command, ..] => Err(Error::InvalidInput(format!("unknown command '{command}'"))),
-            [] => Err(Error::InvalidInput("missing command".to_string())),
+            ["cd"] => Err(Error::invalid_input("wrong cd command: missing target directory")),
+            [command, ..] => Err(Error::invalid_input(format!("unknown command '{command}'")).within(s, command)),
+            [] => Err(Error::invalid_input("missing command")),
         }
     }
 }
@@ -40,6 +38,12 @@ impl ChangeDirectory {
     }
 }
 
+impl fmt::Display for ChangeDirectory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cd {}", self.target)
+    }
+}
+
 impl Command for ChangeDirectory {
     fn execute(&self, context: &mut Context) -> Result<()> {
         let target = self.target.as_str();
@@ -78,7 +82,7 @@ impl Command for ChangeDirectory {
     }
 
     fn add_output(&mut self, _output: &str) -> Result<()> {
-        Err(Error::InvalidInput(format!(
+        Err(Error::invalid_input(format!(
             "cd {}: command does not any output",
             self.target
         )))
@@ -87,7 +91,13 @@ impl Command for ChangeDirectory {
 
 #[derive(Debug, Default)]
 pub struct ListDirectory {
-    outputs: Vec<(String, Option<BigUint>)>,
+    outputs: Vec<(String, Option<Natural>)>,
+}
+
+impl fmt::Display for ListDirectory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ls")
+    }
 }
 
 impl Command for ListDirectory {
@@ -115,15 +125,16 @@ impl Command for ListDirectory {
             Some((x, name)) => {
                 Ok((
                     name.to_string(),
-                    Some(x.parse::<BigUint>().wrap_err_with(|| {
-                        Error::InvalidInput(format!(
+                    Some(x.parse::<Natural>().map_err(|_| {
+                        Error::invalid_input(format!(
                             "wrong ls output: file size must be a valid unsigned integer (got '{x}')"
                         ))
+                        .within(output, x)
                     })?),
                 ))
             }
             _ => {
-                Err(Error::InvalidInput(format!(
+                Err(Error::invalid_input(format!(
                     "wrong ls output: expected '{{[0-9]+|dir}} {{name}}' (got '{output}')"
                 )))
             }
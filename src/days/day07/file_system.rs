@@ -2,11 +2,13 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use enum_dispatch::enum_dispatch;
-use num_bigint::BigUint;
 
 use super::command::Command;
+use crate::number::Natural;
 use crate::solver::Result;
+use crate::trace::{Event, Tracer};
 
+/// File tree explored by the shell, with the path from the root to its working directory.
 #[derive(Debug)]
 pub struct Context {
     root_node: Rc<RefCell<Node>>,
@@ -39,17 +41,50 @@ impl Context {
         self.node_pointer.last().map(NodeHandle::new)
     }
 
+    /// Absolute path of the working directory, empty when there is none.
+    pub fn working_directory_path(&self) -> String {
+        match self.node_pointer.as_slice() {
+            [] => String::default(),
+            [_] => "/".to_string(),
+            [_, directories @ ..] => {
+                directories
+                    .iter()
+                    .map(|node| format!("/{}", node.borrow().name()))
+                    .collect()
+            }
+        }
+    }
+
     pub fn root(&self) -> NodeHandle {
         NodeHandle::new(&self.root_node)
     }
-    pub fn update(&mut self, commands: &[Box<dyn Command>]) -> Result<()> {
+
+    /// Finds the node at the absolute `path`, whose components are separated by `/`.
+    pub fn find(&self, path: &str) -> Option<NodeHandle> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(self.root(), |node, name| {
+                node.children().into_iter().find(|child| child.name() == name)
+            })
+    }
+
+    /// Executes `commands` in order, reporting each of them to `tracer`.
+    pub fn update(&mut self, commands: &[Box<dyn Command>], tracer: &mut dyn Tracer) -> Result<()> {
         for command in commands.iter() {
             command.execute(self)?;
+
+            if tracer.enabled() {
+                tracer.trace(Event::Command {
+                    command: command.to_string(),
+                    working_directory: self.working_directory_path(),
+                })?;
+            }
         }
 
         Ok(())
     }
 
+    /// Lists the nodes of the tree, the root included, matching `predicate`.
     pub fn browse_from_root(&self, predicate: impl Fn(&NodeHandle) -> bool) -> Vec<NodeHandle> {
         [self.root()]
             .into_iter()
@@ -60,17 +95,19 @@ impl Context {
     }
 }
 
+/// File or directory of the tree, the size of a directory being the total size of its files.
 #[enum_dispatch(Node)]
 pub trait NodeLike {
     fn name(&self) -> String;
     fn add_child(&mut self, node: Node);
     fn children(&self) -> Vec<NodeHandle>;
     fn successors(&self) -> Vec<NodeHandle>;
-    fn size(&self) -> BigUint;
+    fn size(&self) -> Natural;
     fn is_file(&self) -> bool;
     fn is_directory(&self) -> bool;
 }
 
+/// Shared reference to a node of the tree.
 #[derive(Debug, Clone)]
 pub struct NodeHandle {
     inner: Rc<RefCell<Node>>,
@@ -103,7 +140,7 @@ impl NodeLike for NodeHandle {
         self.inner.borrow().successors()
     }
 
-    fn size(&self) -> BigUint {
+    fn size(&self) -> Natural {
         self.inner.borrow().size()
     }
 
@@ -132,7 +169,7 @@ impl Node {
         Self::Directory(DirectoryNode::new(name))
     }
 
-    pub fn new_file(name: String, size: BigUint) -> Self {
+    pub fn new_file(name: String, size: Natural) -> Self {
         Self::File(FileNode::new(name, size))
     }
 }
@@ -140,11 +177,11 @@ impl Node {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileNode {
     name: String,
-    size: BigUint,
+    size: Natural,
 }
 
 impl FileNode {
-    pub fn new(name: String, size: BigUint) -> Self {
+    pub fn new(name: String, size: Natural) -> Self {
         Self { name, size }
     }
 }
@@ -164,7 +201,7 @@ impl NodeLike for FileNode {
         Vec::default()
     }
 
-    fn size(&self) -> BigUint {
+    fn size(&self) -> Natural {
         self.size.clone()
     }
 
@@ -216,7 +253,7 @@ impl NodeLike for DirectoryNode {
             .collect()
     }
 
-    fn size(&self) -> BigUint {
+    fn size(&self) -> Natural {
         self.children.iter().map(|node| node.borrow().size()).sum()
     }
 
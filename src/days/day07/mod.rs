@@ -1,70 +1,175 @@
-mod command;
-mod file_system;
+//! Day 7: No Space Left On Device.
+//!
+//! The input is a shell transcript whose [`command::Command`]s, once replayed, build the file tree of a
+//! [`file_system::Context`].
+//!
+//! ```
+//! use std::io::BufReader;
+//!
+//! use advent_of_code_2022::days::day07::file_system::NodeLike;
+//! use advent_of_code_2022::days::day07::Solver;
+//!
+//! let input = "$ cd /\n$ ls\ndir a\n100 b.txt\n$ cd a\n$ ls\n20 c.txt\n";
+//! let solver = Solver::from_reader(BufReader::new(input.as_bytes()))?;
+//! let context = solver.context()?;
+//!
+//! assert_eq!(context.find("/a").map(|node| node.size()), Some(20u32.into()));
+//! assert_eq!(context.root().size(), 120u32.into());
+//! assert_eq!(solver.used_space()?, 120u32.into());
+//! # Ok::<(), advent_of_code_2022::solver::Error>(())
+//! ```
 
-use std::io::{BufRead, BufReader, Read};
+pub mod command;
+pub mod file_system;
+
+use std::cell::OnceCell;
+use std::io::{BufRead, BufReader, Read, Write};
 
 use itertools::Itertools;
-use num_bigint::BigUint;
 use num_traits::identities::Zero;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 
 use self::command::{Command, CommandFactory};
 use self::file_system::{Context, NodeLike};
-use crate::solver::{Error, PuzzlePart, Result, Solve};
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::number::Natural;
+use crate::solver::{Error, Parameter, ParameterKind, Parameters, Puzzle, PuzzlePart, Query, Records, Result, Solve};
+use crate::trace::{NoTracer, Tracer};
+
+const SIZE: Query = Query {
+    name: "size",
+    arguments: "path",
+    description: "total size of the file or directory at the absolute path (e.g. /a/e)",
+};
+
+const SMALL_DIRECTORY_SIZE: Parameter = Parameter {
+    name: "small-directory-size",
+    description: "size under which a directory is considered small",
+    defaults: ["100000", "100000"],
+    kind: ParameterKind::Unsigned { min: 0, max: u64::MAX },
+};
+
+const DISK_SPACE: Parameter = Parameter {
+    name: "disk-space",
+    description: "total space of the file system",
+    defaults: ["70000000", "70000000"],
+    kind: ParameterKind::Unsigned { min: 0, max: u64::MAX },
+};
+
+const UPDATE_SPACE: Parameter = Parameter {
+    name: "update-space",
+    description: "free space required by the update",
+    defaults: ["30000000", "30000000"],
+    kind: ParameterKind::Unsigned { min: 0, max: u64::MAX },
+};
+
+pub const PUZZLE: Puzzle = Puzzle {
+    day: 7,
+    title: "No Space Left On Device",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[SMALL_DIRECTORY_SIZE, DISK_SPACE, UPDATE_SPACE],
+    queries: &[SIZE],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: None,
+    generator: &Generator,
+};
 
 pub struct Solver {
     commands: Vec<Box<dyn Command>>,
+    /// File tree built by replaying the commands, shared by both parts.
+    context: OnceCell<Context>,
 }
 
 impl Solver {
     pub fn from_reader<R: Read>(reader: BufReader<R>) -> Result<Self> {
-        let commands = reader.lines().fold(Ok::<_, Error>(Vec::default()), |commands, line| {
-            let (mut commands, line) = (commands?, line?);
+        Self::from_reader_with(reader, &mut Records::default())
+    }
 
-            if let Some(s) = line.strip_prefix('$') {
-                commands.push(CommandFactory::parse_str(s)?);
-            } else {
-                commands
-                    .last_mut()
-                    .ok_or_else(|| Error::InvalidInput(format!("missing outputs' command: {line}")))?
-                    .add_output(&line)?;
-            }
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with<R: Read>(reader: BufReader<R>, records: &mut Records) -> Result<Self> {
+        // outputs of a malformed command are skipped, having no command to be checked against
+        let mut skipping = false;
+        let commands = reader
+            .lines()
+            .enumerate()
+            .fold(Ok::<_, Error>(Vec::default()), |commands, (i, line)| {
+                let (mut commands, line) = (commands?, line?);
 
-            Ok(commands)
-        })?;
+                if let Some(s) = line.strip_prefix('$') {
+                    let command = records
+                        .check(CommandFactory::parse_str(s).map_err(|e| e.within(&line, s).on_line(i + 1, &line)))?;
 
-        Ok(Self { commands })
+                    skipping = command.is_none();
+                    commands.extend(command);
+                } else if !skipping {
+                    records.check(
+                        commands
+                            .last_mut()
+                            .ok_or_else(|| Error::invalid_input(format!("missing outputs' command: {line}")))
+                            .and_then(|command| command.add_output(&line))
+                            .map_err(|e| e.on_line(i + 1, &line)),
+                    )?;
+                }
+
+                Ok(commands)
+            })?;
+
+        records.finish()?;
+
+        Ok(Self {
+            commands,
+            context: OnceCell::default(),
+        })
     }
-}
 
-impl Solve for Solver {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String> {
+    /// File tree built by replaying the commands, once for all the calls.
+    pub fn context(&self) -> Result<&Context> {
+        if let Some(context) = self.context.get() {
+            return Ok(context);
+        }
+
+        let context = self.replay(&mut NoTracer)?;
+
+        Ok(self.context.get_or_init(|| context))
+    }
+
+    /// Builds a file tree by replaying the commands, reporting each of them to `tracer`.
+    fn replay(&self, tracer: &mut dyn Tracer) -> Result<Context> {
         let mut context = Context::default();
 
-        context.update(&self.commands)?;
+        context.update(&self.commands, tracer)?;
+
+        Ok(context)
+    }
 
-        match puzzle_part {
+    /// Solves the puzzle part selected by `parameters` on the file tree `context`.
+    fn solve_in(context: &Context, parameters: &Parameters) -> Result<Answer> {
+        match parameters.puzzle_part() {
             PuzzlePart::One => {
+                let small_directory_size = parameters.get::<Natural>(&SMALL_DIRECTORY_SIZE)?;
                 let small_directories =
-                    context.browse_from_root(|node| node.is_directory() && node.size() < 100_000u64.into());
+                    context.browse_from_root(|node| node.is_directory() && node.size() < small_directory_size);
 
                 (!small_directories.is_empty())
                     .then(|| {
                         small_directories
                             .into_iter()
                             .map(|node| node.size())
-                            .sum::<BigUint>()
-                            .to_string()
+                            .sum::<Natural>()
+                            .into()
                     })
-                    .ok_or_else(|| Error::NoSolution("no directory smaller than 100000".to_string()))
+                    .ok_or_else(|| Error::NoSolution(format!("no directory smaller than {small_directory_size}")))
             }
             PuzzlePart::Two => {
-                let update_space = 30_000_000u64.into();
-                let total_space = 70_000_000u64.into();
+                let update_space = parameters.get::<Natural>(&UPDATE_SPACE)?;
+                let total_space = parameters.get::<Natural>(&DISK_SPACE)?;
                 let used_space = context.root().size();
 
                 if used_space > total_space {
                     return Err(Error::NoSolution(format!(
-                        "used space overflow total disk space: {used_space} > 70000000"
+                        "used space overflow total disk space: {used_space} > {total_space}"
                     )));
                 }
                 let free_space = total_space - used_space;
@@ -85,9 +190,156 @@ impl Solve for Solver {
                     .take(1)
                     .collect::<Vec<_>>()
                     .get(0)
-                    .unwrap_or(&BigUint::zero())
-                    .to_string())
+                    .unwrap_or(&Natural::zero())
+                    .clone()
+                    .into())
+            }
+        }
+    }
+
+    /// Total size of the files of the file system.
+    pub fn used_space(&self) -> Result<Natural> {
+        Ok(self.context()?.root().size())
+    }
+}
+
+impl Solve for Solver {
+    fn solve_with(&self, parameters: &Parameters, tracer: &mut dyn Tracer) -> Result<Answer> {
+        // commands are replayed for every traced part, the trace not depending on the parts solved before
+        if tracer.enabled() {
+            Self::solve_in(&self.replay(tracer)?, parameters)
+        } else {
+            Self::solve_in(self.context()?, parameters)
+        }
+    }
+
+    fn query(&self, name: &str, arguments: &[&str]) -> Result<Answer> {
+        match name {
+            "size" => {
+                let path = SIZE.argument::<String>(arguments, 0, "a path")?;
+
+                self.context()?
+                    .find(&path)
+                    .map(|node| node.size().into())
+                    .ok_or_else(|| Error::invalid_input(format!("size: no such file or directory '{path}'")))
+            }
+            _ => Err(Error::invalid_input(format!("unknown query '{name}'"))),
+        }
+    }
+}
+
+/// Generates the transcript of the exploration of a file system made of `size` files and directories.
+///
+/// The file system always uses between 45M and 55M of the disk and contains an empty directory, so that both parts
+/// have a solution. A file is added when none is drawn, e.g. when `size` is 0.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        // children of each directory, the root being the first one
+        let mut directories = vec![Vec::default()];
+        let mut files = 0;
+
+        for i in 0..size {
+            let parent = rng.gen_range(0..directories.len());
+
+            if rng.gen_bool(0.25) {
+                let child = directories.len();
+
+                directories[parent].push(GeneratedNode::Directory(i, child));
+                directories.push(Vec::default());
+            } else {
+                directories[parent].push(GeneratedNode::File(i));
+                files += 1;
             }
         }
+
+        // a file system without files would use none of the disk
+        if files == 0 {
+            directories[0].push(GeneratedNode::File(size + 1));
+            files = 1;
+        }
+
+        let empty_directory = directories.len();
+
+        directories[0].push(GeneratedNode::Directory(size, empty_directory));
+        directories.push(Vec::default());
+
+        let average_size = 50_000_000 / files;
+
+        writeln!(writer, "$ cd /")?;
+
+        // depth-first exploration, `None` going back to the parent directory
+        let mut stack = vec![Some((None, 0))];
+
+        while let Some(entry) = stack.pop() {
+            let Some((name, directory)) = entry else {
+                writeln!(writer, "$ cd ..")?;
+
+                continue;
+            };
+
+            if let Some(name) = name {
+                writeln!(writer, "$ cd d{name}")?;
+                stack.push(None);
+            }
+
+            writeln!(writer, "$ ls")?;
+
+            for node in directories[directory].iter() {
+                match node {
+                    GeneratedNode::Directory(name, child) => {
+                        writeln!(writer, "dir d{name}")?;
+                        stack.push(Some((Some(*name), *child)));
+                    }
+                    GeneratedNode::File(name) => {
+                        let size = rng.gen_range(average_size * 9 / 10..=average_size * 11 / 10);
+
+                        writeln!(writer, "{size} f{name}.txt")?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+enum GeneratedNode {
+    /// Name and index of a directory.
+    Directory(usize, usize),
+    File(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::JsonLinesTracer;
+
+    fn trace(solver: &Solver, puzzle_part: PuzzlePart) -> String {
+        let mut tracer = JsonLinesTracer::new(Vec::default());
+
+        // leaves just enough free space for part two to have a solution on a small input
+        let parameters = Parameters::preset(puzzle_part)
+            .with_assignments(&PUZZLE, &["disk-space=30000100".to_string()])
+            .unwrap();
+
+        solver.solve_with(&parameters, &mut tracer).unwrap();
+
+        String::from_utf8(tracer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn every_part_traces_the_commands() {
+        let input = "$ cd /\n$ ls\ndir a\n100 b.txt\n$ cd a\n$ ls\n20 c.txt\n";
+        let solver = Solver::from_reader(BufReader::new(input.as_bytes())).unwrap();
+        let alone = trace(
+            &Solver::from_reader(BufReader::new(input.as_bytes())).unwrap(),
+            PuzzlePart::Two,
+        );
+
+        assert_eq!(alone.lines().count(), 4);
+        assert_eq!(trace(&solver, PuzzlePart::One), alone);
+        assert_eq!(trace(&solver, PuzzlePart::Two), alone);
     }
 }
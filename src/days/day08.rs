@@ -1,17 +1,46 @@
-use std::io::{BufRead, BufReader, Read};
+//! Day 8: Treetop Tree House.
+//!
+//! The input is a grid of [`Tree`]s, read by rows.
+//!
+//! ```
+//! use std::io::BufReader;
+//!
+//! use advent_of_code_2022::days::day08::Solver;
+//!
+//! let solver = Solver::from_reader(BufReader::new("303\n255\n653\n".as_bytes()))?;
+//!
+//! assert_eq!(solver.forest()[1][2].height(), 5);
+//! assert_eq!(solver.scenic_score(1, 1), 1);
+//! assert_eq!(solver.compute_visible_trees(), 9);
+//! # Ok::<(), advent_of_code_2022::solver::Error>(())
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
 
 use itertools::Itertools;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 
-use crate::solver::{Error, PuzzlePart, Result, Solve};
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::solver::{Error, Parameters, Puzzle, PuzzlePart, Query, Records, Result, Solve};
+use crate::trace::Tracer;
 
+/// Tree of a height from 0 to 9.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Tree(u8);
 
 impl Tree {
+    pub fn height(&self) -> u8 {
+        self.0 - b'0'
+    }
+
+    /// Whether this tree is taller than all the `trees`.
     pub fn is_taller_than<'a>(&self, mut trees: impl Iterator<Item = &'a Self>) -> bool {
         trees.all(|tree| tree.0 < self.0)
     }
 
+    /// Number of `trees` seen from this tree, up to the first one at least as tall.
     pub fn distance_from<'a>(&self, trees: impl Iterator<Item = &'a Self>) -> usize {
         let (max_distance, _) = trees.size_hint();
 
@@ -29,30 +58,86 @@ impl TryFrom<char> for Tree {
     fn try_from(c: char) -> Result<Self> {
         c.is_ascii_digit()
             .then_some(Self(c as u8))
-            .ok_or_else(|| Error::InvalidInput(format!("wrong tree: expected digit (got '{c}')")))
+            .ok_or_else(|| Error::invalid_input(format!("wrong tree: expected digit (got '{c}')")))
     }
 }
 
+/// Parses a row of trees, its error listing every wrong tree with its 1-based column.
+fn parse_row(line: &str) -> Result<Vec<Tree>> {
+    let mut wrong = Vec::default();
+    let trees = line
+        .char_indices()
+        .filter_map(|(j, c)| Tree::try_from(c).map_err(|_| wrong.push((j, c))).ok())
+        .collect();
+
+    let (Some((first, _)), Some((last, c))) = (wrong.first(), wrong.last()) else {
+        return Ok(trees);
+    };
+    let got = wrong
+        .iter()
+        .map(|(j, c)| format!("'{c}' at column {}", line[..*j].chars().count() + 1))
+        .join(", ");
+
+    Err(
+        Error::invalid_input(format!("wrong trees: expected digits (got {got})"))
+            .within(line, &line[*first..last + c.len_utf8()]),
+    )
+}
+
+const SCENIC_SCORE: Query = Query {
+    name: "scenic",
+    arguments: "x y",
+    description: "scenic score of the tree at column x and row y, both starting from 0",
+};
+
+pub const PUZZLE: Puzzle = Puzzle {
+    day: 8,
+    title: "Treetop Tree House",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[],
+    queries: &[SCENIC_SCORE],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: None,
+    generator: &Generator,
+};
+
 pub struct Solver {
     forest: Vec<Vec<Tree>>,
 }
 
 impl Solver {
     pub fn from_reader<R: Read>(reader: BufReader<R>) -> Result<Self> {
-        let forest = reader
-            .lines()
-            .map(|line| line?.chars().map(Tree::try_from).collect::<Result<Vec<_>>>())
-            .collect::<Result<Vec<_>>>()?;
+        Self::from_reader_with(reader, &mut Records::default())
+    }
+
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with<R: Read>(reader: BufReader<R>, records: &mut Records) -> Result<Self> {
+        let lines = reader.lines().collect::<std::io::Result<Vec<_>>>()?;
+
+        let forest = records.collect::<_, Vec<_>>(
+            lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| parse_row(line).map_err(|e| e.on_line(i + 1, line))),
+        )?;
+
+        records.finish()?;
 
         if forest.is_empty() || forest.iter().all(|trees| trees.is_empty()) {
             Err(Error::EmptyInput)
-        } else if !forest.iter().skip(1).all(|trees| trees.len() == forest[0].len()) {
-            Err(Error::InvalidInput("trees are not planted in a grid".to_string()))
+        } else if let Some(i) = forest.iter().position(|trees| trees.len() != forest[0].len()) {
+            Err(Error::invalid_input("trees are not planted in a grid").on_line(i + 1, &lines[i]))
         } else {
             Ok(Self { forest })
         }
     }
 
+    /// Rows of trees, from the top one.
+    pub fn forest(&self) -> &[Vec<Tree>] {
+        &self.forest
+    }
+
+    /// Row and column of the tree at column `x` and row `y`.
     pub fn get_trees_from_cartesian_coordinates(&self, x: usize, y: usize) -> (Vec<Tree>, Vec<Tree>) {
         (
             self.forest[y].clone(),
@@ -84,28 +169,66 @@ impl Solver {
                 .count()
     }
 
+    /// Scenic score of the tree at column `x` and row `y`, which must be in the forest.
+    pub fn scenic_score(&self, x: usize, y: usize) -> usize {
+        let tree = self.forest[y][x];
+        let (horizontal_trees, vertical_trees) = self.get_trees_from_cartesian_coordinates(x, y);
+
+        tree.distance_from(horizontal_trees[..x].iter().rev())
+            * tree.distance_from(horizontal_trees[x + 1..].iter())
+            * tree.distance_from(vertical_trees[..y].iter().rev())
+            * tree.distance_from(vertical_trees[y + 1..].iter())
+    }
+
     pub fn compute_highest_scenic_view(&self) -> usize {
         (1..self.forest.len() - 1)
             .cartesian_product(1..self.forest[0].len() - 1)
-            .map(|(y, x)| {
-                let tree = self.forest[y][x];
-                let (horizontal_trees, vertical_trees) = self.get_trees_from_cartesian_coordinates(x, y);
-
-                tree.distance_from(horizontal_trees[..x].iter().rev())
-                    * tree.distance_from(horizontal_trees[x + 1..].iter())
-                    * tree.distance_from(vertical_trees[..y].iter().rev())
-                    * tree.distance_from(vertical_trees[y + 1..].iter())
-            })
+            .map(|(y, x)| self.scenic_score(x, y))
             .max()
             .unwrap_or_default()
     }
 }
 
 impl Solve for Solver {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String> {
-        match puzzle_part {
-            PuzzlePart::One => Ok(self.compute_visible_trees().to_string()),
-            PuzzlePart::Two => Ok(self.compute_highest_scenic_view().to_string()),
+    fn solve_with(&self, parameters: &Parameters, _tracer: &mut dyn Tracer) -> Result<Answer> {
+        match parameters.puzzle_part() {
+            PuzzlePart::One => Ok(self.compute_visible_trees().into()),
+            PuzzlePart::Two => Ok(self.compute_highest_scenic_view().into()),
+        }
+    }
+
+    fn query(&self, name: &str, arguments: &[&str]) -> Result<Answer> {
+        match name {
+            "scenic" => {
+                let x = SCENIC_SCORE.argument(arguments, 0, "an unsigned integer")?;
+                let y = SCENIC_SCORE.argument(arguments, 1, "an unsigned integer")?;
+
+                if y >= self.forest.len() || x >= self.forest[0].len() {
+                    return Err(Error::invalid_input(format!(
+                        "no tree at ({x}, {y}) in a forest of {} x {} trees",
+                        self.forest[0].len(),
+                        self.forest.len()
+                    )));
+                }
+
+                Ok(self.scenic_score(x, y).into())
+            }
+            _ => Err(Error::invalid_input(format!("unknown query '{name}'"))),
+        }
+    }
+}
+
+/// Generates a forest of `size` x `size` trees.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        for _ in 0..size.max(1) {
+            let trees = (0..size.max(1)).map(|_| rng.gen_range(b'0'..=b'9')).collect::<Vec<_>>();
+
+            writeln!(writer, "{}", String::from_utf8_lossy(&trees))?;
         }
+
+        Ok(())
     }
 }
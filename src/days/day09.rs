@@ -1,13 +1,43 @@
+//! Day 9: Rope Bridge.
+//!
+//! The input lists the [`Movement`]s of the head of a rope, whose knots follow each other.
+//!
+//! ```
+//! use std::io::BufReader;
+//!
+//! use advent_of_code_2022::days::day09::{Direction, Position, Solver};
+//!
+//! let solver = Solver::from_reader(BufReader::new("R 2\nU 1\n".as_bytes()))?;
+//!
+//! assert_eq!(solver.movements().len(), 2);
+//!
+//! let mut head = Position::default();
+//! let mut tail = Position::default();
+//!
+//! head.advance(Direction::Right);
+//! head.advance(Direction::Right);
+//! tail.follow(&head)?;
+//!
+//! assert_eq!((tail.x(), tail.y()), (&1.into(), &0.into()));
+//! # Ok::<(), advent_of_code_2022::solver::Error>(())
+//! ```
+
 use std::collections::HashSet;
-use std::io::{BufRead, BufReader, Read};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::str::FromStr;
 
-use eyre::Context;
-use num_bigint::BigInt;
 use num_traits::identities::Zero;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 use strum::{Display, EnumString};
 
-use crate::solver::{Error, PuzzlePart, Result, Solve};
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::number::Integer;
+use crate::solver::{Error, Parameter, ParameterKind, Parameters, Puzzle, PuzzlePart, Records, Result, Solve};
+use crate::trace::{Event, NoTracer, Tracer};
 
 #[derive(Debug, Copy, Clone, EnumString, Display)]
 pub enum Direction {
@@ -21,18 +51,38 @@ pub enum Direction {
     Down,
 }
 
+/// Position of a knot, `y` growing upwards.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct Position {
-    x: BigInt,
-    y: BigInt,
+    x: Integer,
+    y: Integer,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
 }
 
 impl Position {
-    pub fn follow(&mut self, target: &Position) {
-        let (x, y) = match (
-            (target.x.clone() - self.x.clone()).try_into().unwrap(),
-            (target.y.clone() - self.y.clone()).try_into().unwrap(),
-        ) {
+    pub fn x(&self) -> &Integer {
+        &self.x
+    }
+
+    pub fn y(&self) -> &Integer {
+        &self.y
+    }
+
+    /// Moves towards `target` so that both knots touch, fails when they are too far apart.
+    pub fn follow(&mut self, target: &Position) -> Result<()> {
+        let distance = |a: &Integer, b: &Integer| {
+            (a - b)
+                .to_small()
+                .and_then(|distance| i32::try_from(distance).ok())
+                .ok_or_else(|| Error::NoSolution(format!("knot at {self} is too far from its leader at {target}")))
+        };
+
+        let (x, y) = match (distance(&target.x, &self.x)?, distance(&target.y, &self.y)?) {
             (0, 0) | (1, 1) | (-1, -1) | (-1, 1) | (1, -1) | (1, 0) | (-1, 0) | (0, 1) | (0, -1) => (0, 0),
             (0, y) => (0, if y < 0 { y + 1 } else { y - 1 }),
             (x, 0) => (if x < 0 { x + 1 } else { x - 1 }, 0),
@@ -46,6 +96,8 @@ impl Position {
 
         self.x += x;
         self.y += y;
+
+        Ok(())
     }
 
     pub fn advance(&mut self, direction: Direction) {
@@ -61,78 +113,179 @@ impl Position {
     }
 }
 
+/// Move of the head by `magnitude` steps.
 pub struct Movement {
-    magnitude: BigInt,
+    magnitude: Integer,
     direction: Direction,
 }
 
+impl Movement {
+    pub fn magnitude(&self) -> &Integer {
+        &self.magnitude
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+}
+
 impl FromStr for Movement {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
         s.split_once(' ')
             .ok_or_else(|| {
-                Error::InvalidInput(format!(
+                Error::invalid_input(format!(
                     "wrong move: expected '{{direction}} {{magnitude}}' (got '{s}')"
                 ))
             })
             .and_then(|(direction, magnitude)| {
                 Ok(Self {
-                    magnitude: magnitude.parse().wrap_err_with(|| {
-                        Error::InvalidInput(format!("wrong magnitude: expected a valid integer (got '{magnitude}')"))
+                    magnitude: magnitude.parse().map_err(|_| {
+                        Error::invalid_input(format!("wrong magnitude: expected a valid integer (got '{magnitude}')"))
+                            .within(s, magnitude)
                     })?,
-                    direction: direction.parse().wrap_err_with(|| {
-                        Error::InvalidInput(format!("wrong direction: expected '{{R|L|U|D}}' (got '{direction}')"))
+                    direction: direction.parse().map_err(|_| {
+                        Error::invalid_input(format!("wrong direction: expected '{{R|L|U|D}}' (got '{direction}')"))
+                            .within(s, direction)
                     })?,
                 })
             })
     }
 }
 
+const KNOTS: Parameter = Parameter {
+    name: "knots",
+    description: "number of knots of the rope, head included",
+    defaults: ["2", "10"],
+    kind: ParameterKind::Unsigned { min: 1, max: 1000 },
+};
+
+pub const PUZZLE: Puzzle = Puzzle {
+    day: 9,
+    title: "Rope Bridge",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[KNOTS],
+    queries: &[],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: Some(|reader, parameters| Solver::solve_streaming(reader, parameters)),
+    generator: &Generator,
+};
+
 pub struct Solver {
     movements: Vec<Movement>,
 }
 
 impl Solver {
     pub fn from_reader<R: Read>(reader: BufReader<R>) -> Result<Self> {
-        let movements = reader.lines().map(|line| line?.parse()).collect::<Result<Vec<_>>>()?;
+        Self::from_reader_with(reader, &mut Records::default())
+    }
+
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with<R: Read>(reader: BufReader<R>, records: &mut Records) -> Result<Self> {
+        let movements = records.collect::<_, Vec<_>>(reader.lines().enumerate().map(|(i, line)| {
+            let line = line?;
+
+            line.parse().map_err(|e: Error| e.on_line(i + 1, &line))
+        }))?;
+
+        records.finish()?;
 
         Ok(Self { movements })
     }
+
+    pub fn movements(&self) -> &[Movement] {
+        &self.movements
+    }
+
+    /// Solves while reading the input, moving the rope as soon as a movement is read.
+    ///
+    /// Only the positions visited by the tail are kept, which the answer counts anyway.
+    pub fn solve_streaming(reader: impl BufRead, parameters: &Parameters) -> Result<Answer> {
+        let mut rope = Rope::new(parameters.get(&KNOTS)?);
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let movement = line.parse::<Movement>().map_err(|e| e.on_line(i + 1, &line))?;
+
+            rope.apply(&movement, &mut NoTracer)?;
+        }
+
+        Ok(rope.visited_positions.len().into())
+    }
 }
 
-impl Solve for Solver {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String> {
-        let size = match puzzle_part {
-            PuzzlePart::One => 2,
-            PuzzlePart::Two => 10,
-        };
+/// Knots of a rope, with the positions visited by its tail.
+struct Rope {
+    knots: Vec<Position>,
+    visited_positions: HashSet<Position>,
+}
 
-        let mut knots = vec![Position::default(); size];
-        let mut registered_positions = [Position::default()].into_iter().collect::<HashSet<_>>();
+impl Rope {
+    fn new(size: usize) -> Self {
+        Self {
+            knots: vec![Position::default(); size],
+            visited_positions: [Position::default()].into_iter().collect(),
+        }
+    }
 
-        for movement in self.movements.iter() {
-            let mut magnitude = BigInt::zero();
+    fn apply(&mut self, movement: &Movement, tracer: &mut dyn Tracer) -> Result<()> {
+        let knots = &mut self.knots;
+        let mut magnitude = Integer::zero();
 
-            // unfortunately `BigInt` does not implement `Step`
-            while magnitude < movement.magnitude {
-                knots[0].advance(movement.direction);
+        // unfortunately `Integer` does not implement `Step`
+        while magnitude < movement.magnitude {
+            knots[0].advance(movement.direction);
 
-                let mut i = 0;
+            let mut i = 0;
 
-                while i < knots.len() - 1 {
-                    let parent = knots[i].clone();
-                    let child = &mut knots[i + 1];
+            while i < knots.len() - 1 {
+                let parent = knots[i].clone();
+                let child = &mut knots[i + 1];
 
-                    child.follow(&parent);
-                    i += 1;
-                }
+                child.follow(&parent)?;
+                i += 1;
+            }
 
-                registered_positions.insert(knots.last().unwrap().clone());
-                magnitude += 1;
+            if tracer.enabled() {
+                tracer.trace(Event::Step {
+                    knots: knots.iter().map(|knot| (knot.x.clone(), knot.y.clone())).collect(),
+                })?;
             }
+            self.visited_positions.insert(knots.last().unwrap().clone());
+            magnitude += 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl Solve for Solver {
+    fn solve_with(&self, parameters: &Parameters, tracer: &mut dyn Tracer) -> Result<Answer> {
+        let mut rope = Rope::new(parameters.get(&KNOTS)?);
+
+        for movement in self.movements.iter() {
+            rope.apply(movement, tracer)?;
+        }
+
+        Ok(rope.visited_positions.len().into())
+    }
+}
+
+/// Generates `size` moves of 1 to 20 steps.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        for _ in 0..size {
+            let direction = [Direction::Right, Direction::Left, Direction::Up, Direction::Down]
+                .choose(rng)
+                .copied()
+                .unwrap();
+
+            writeln!(writer, "{direction} {}", rng.gen_range(1..=20))?;
         }
 
-        Ok(registered_positions.len().to_string())
+        Ok(())
     }
 }
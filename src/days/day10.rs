@@ -1,13 +1,38 @@
-use std::io::{BufRead, BufReader, Read};
+//! Day 10: Cathode-Ray Tube.
+//!
+//! The input is a program of [`Command`]s run by a CPU whose single register X positions the sprite of the CRT.
+//!
+//! ```
+//! use std::io::BufReader;
+//!
+//! use advent_of_code_2022::days::day10::{Command, CommandLike, Cycle, Solver};
+//!
+//! let solver = Solver::from_reader(BufReader::new("noop\naddx 3\n".as_bytes()))?;
+//! let mut register = 1.into();
+//! let mut command = solver.commands()[1].clone();
+//!
+//! assert!(matches!(command, Command::AddX(_)));
+//! assert!(matches!(command.execute(&mut register), Cycle::Wait));
+//! assert!(matches!(command.execute(&mut register), Cycle::Done));
+//! assert_eq!(register, 4.into());
+//! # Ok::<(), advent_of_code_2022::solver::Error>(())
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
 use std::str::FromStr;
 
 use enum_dispatch::enum_dispatch;
-use eyre::Context;
-use num_bigint::BigInt;
 use num_traits::identities::Zero;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 
-use crate::solver::{Error, PuzzlePart, Result, Solve};
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::number::Integer;
+use crate::solver::{Error, Parameter, ParameterKind, Parameters, Puzzle, PuzzlePart, Records, Result, Solve};
+use crate::trace::{Event, NoTracer, Tracer};
 
+/// State of a command at the end of a cycle.
 pub enum Cycle {
     Wait,
     Done,
@@ -15,7 +40,8 @@ pub enum Cycle {
 
 #[enum_dispatch(Command)]
 pub trait CommandLike {
-    fn execute(&mut self, register: &mut BigInt) -> Cycle;
+    /// Runs one cycle of the command, updating `register` on its last cycle.
+    fn execute(&mut self, register: &mut Integer) -> Cycle;
 }
 
 #[derive(Clone, Debug)]
@@ -30,21 +56,24 @@ impl FromStr for Command {
 
     fn from_str(s: &str) -> Result<Self> {
         if s.starts_with("noop") {
-            (s == "noop")
-                .then_some(Self::Noop(NoopCommand))
-                .ok_or_else(|| Error::InvalidInput(format!("wrong command: noop has no argument (got '{s}')")))
+            (s == "noop").then_some(Self::Noop(NoopCommand)).ok_or_else(|| {
+                Error::invalid_input(format!("wrong command: noop has no argument (got '{s}')")).within(s, &s[4..])
+            })
         } else if s.starts_with("addx") {
             s.split_once(' ')
-                .ok_or_else(|| Error::InvalidInput(format!("wrong command: expected 'addx [0-9]+' (got '{s}')")))
+                .ok_or_else(|| Error::invalid_input(format!("wrong command: expected 'addx [0-9]+' (got '{s}')")))
                 .and_then(|(_, value)| {
-                    let value = value.parse().wrap_err_with(|| {
-                        format!("wrong command: addx argument must be a valid integer (got '{value}')")
+                    let value = value.parse().map_err(|_| {
+                        Error::invalid_input(format!(
+                            "wrong command: addx argument must be a valid integer (got '{value}')"
+                        ))
+                        .within(s, value)
                     })?;
 
                     Ok(Self::AddX(AddXCommand::new(value)))
                 })
         } else {
-            Err(Error::InvalidInput(format!("unknown '{s}' command")))
+            Err(Error::invalid_input(format!("unknown '{s}' command")))
         }
     }
 }
@@ -53,28 +82,28 @@ impl FromStr for Command {
 pub struct NoopCommand;
 
 impl CommandLike for NoopCommand {
-    fn execute(&mut self, _register: &mut BigInt) -> Cycle {
+    fn execute(&mut self, _register: &mut Integer) -> Cycle {
         Cycle::Done
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct AddXCommand {
-    value: BigInt,
+    value: Integer,
     state: bool,
 }
 
 impl AddXCommand {
-    pub fn new(value: BigInt) -> Self {
+    pub fn new(value: Integer) -> Self {
         Self { value, state: false }
     }
 }
 
 impl CommandLike for AddXCommand {
-    fn execute(&mut self, register: &mut BigInt) -> Cycle {
+    fn execute(&mut self, register: &mut Integer) -> Cycle {
         match self.state {
             true => {
-                *register += self.value.clone();
+                *register += &self.value;
                 self.state = false;
 
                 Cycle::Done
@@ -88,74 +117,208 @@ impl CommandLike for AddXCommand {
     }
 }
 
+const FIRST_CYCLE: Parameter = Parameter {
+    name: "first-cycle",
+    description: "first cycle whose signal strength is summed",
+    defaults: ["20", "20"],
+    kind: ParameterKind::Unsigned {
+        min: 1,
+        max: MAX_CYCLES,
+    },
+};
+
+const CYCLE_STEP: Parameter = Parameter {
+    name: "cycle-step",
+    description: "number of cycles between two summed signal strengths",
+    defaults: ["40", "40"],
+    kind: ParameterKind::Unsigned {
+        min: 1,
+        max: MAX_CYCLES,
+    },
+};
+
+const LAST_CYCLE: Parameter = Parameter {
+    name: "last-cycle",
+    description: "last cycle whose signal strength may be summed",
+    defaults: ["220", "220"],
+    kind: ParameterKind::Unsigned {
+        min: 1,
+        max: MAX_CYCLES,
+    },
+};
+
+const SCREEN_WIDTH: Parameter = Parameter {
+    name: "screen-width",
+    description: "number of pixels per CRT row",
+    defaults: ["40", "40"],
+    kind: ParameterKind::Unsigned { min: 1, max: 1000 },
+};
+
+const SCREEN_HEIGHT: Parameter = Parameter {
+    name: "screen-height",
+    description: "number of CRT rows",
+    defaults: ["6", "6"],
+    kind: ParameterKind::Unsigned { min: 1, max: 1000 },
+};
+
+/// Bound of the cycle parameters, keeping the run time reasonable.
+const MAX_CYCLES: u64 = 1_000_000;
+
+pub const PUZZLE: Puzzle = Puzzle {
+    day: 10,
+    title: "Cathode-Ray Tube",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[FIRST_CYCLE, CYCLE_STEP, LAST_CYCLE, SCREEN_WIDTH, SCREEN_HEIGHT],
+    queries: &[],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: Some(|reader, parameters| Solver::solve_streaming(reader, parameters)),
+    generator: &Generator,
+};
+
 pub struct Solver {
     commands: Vec<Command>,
 }
 
 impl Solver {
     pub fn from_reader<R: Read>(reader: BufReader<R>) -> Result<Self> {
-        let commands = reader.lines().map(|line| line?.parse()).collect::<Result<Vec<_>>>()?;
+        Self::from_reader_with(reader, &mut Records::default())
+    }
+
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with<R: Read>(reader: BufReader<R>, records: &mut Records) -> Result<Self> {
+        let commands = records.collect::<_, Vec<_>>(reader.lines().enumerate().map(|(i, line)| {
+            let line = line?;
+
+            line.parse().map_err(|e: Error| e.on_line(i + 1, &line))
+        }))?;
+
+        records.finish()?;
 
         Ok(Self { commands })
     }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
 }
 
-impl Solve for Solver {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String> {
-        let mut commands = self.commands.iter().cloned().rev().collect::<Vec<_>>();
-        let mut register = BigInt::from(1);
-
-        match puzzle_part {
-            PuzzlePart::One => {
-                let mut command = commands.pop();
-                let mut computed_value = BigInt::zero();
-                let mut watched_cycle = 20;
-
-                for n in 1..=220 {
-                    if n == watched_cycle {
-                        computed_value += register.clone() * watched_cycle;
-                        watched_cycle += 40;
-                    }
-
-                    if let Cycle::Done = command
-                        .as_mut()
-                        .map(|command| command.execute(&mut register))
-                        .unwrap_or(Cycle::Wait)
-                    {
-                        command = commands.pop();
-                    }
+impl Solver {
+    /// Solves while reading the input, each command being read when the previous one is done.
+    ///
+    /// The commands following the last cycle of the CRT are still read to report invalid ones.
+    pub fn solve_streaming(reader: impl BufRead, parameters: &Parameters) -> Result<Answer> {
+        let mut commands = reader.lines().enumerate().map(|(i, line)| {
+            let line = line?;
+
+            line.parse().map_err(|e: Error| e.on_line(i + 1, &line))
+        });
+
+        let answer = run(&mut commands, parameters, &mut NoTracer)?;
+
+        for command in commands {
+            command?;
+        }
+
+        Ok(answer)
+    }
+}
+
+/// Runs the program of `commands` until the last cycle needed by the puzzle part.
+fn run(
+    commands: &mut dyn Iterator<Item = Result<Command>>,
+    parameters: &Parameters,
+    tracer: &mut dyn Tracer,
+) -> Result<Answer> {
+    let mut register = Integer::from(1);
+
+    match parameters.puzzle_part() {
+        PuzzlePart::One => {
+            let first_cycle = parameters.get::<usize>(&FIRST_CYCLE)?;
+            let cycle_step = parameters.get::<usize>(&CYCLE_STEP)?;
+            let last_cycle = parameters.get::<usize>(&LAST_CYCLE)?;
+            let mut command = commands.next().transpose()?;
+            let mut computed_value = Integer::zero();
+
+            for n in 1..=last_cycle {
+                if tracer.enabled() {
+                    tracer.trace(Event::Cycle {
+                        cycle: n,
+                        x: register.clone(),
+                    })?;
+                }
+
+                if n >= first_cycle && (n - first_cycle) % cycle_step == 0 {
+                    computed_value += &register * (n as i64);
                 }
 
-                Ok(computed_value.to_string())
+                if let Cycle::Done = command
+                    .as_mut()
+                    .map(|command| command.execute(&mut register))
+                    .unwrap_or(Cycle::Wait)
+                {
+                    command = commands.next().transpose()?;
+                }
             }
-            PuzzlePart::Two => {
-                let mut command = commands.pop();
-                let mut result = String::default();
-
-                for n in 0..240 {
-                    let x = n % 40;
-
-                    if register == x.into() || register == (x - 1).into() || register == (x + 1).into() {
-                        result.push('#');
-                    } else {
-                        result.push('.');
-                    }
-
-                    if x == 39 {
-                        result.push('\n');
-                    }
-
-                    if let Cycle::Done = command
-                        .as_mut()
-                        .map(|command| command.execute(&mut register))
-                        .unwrap_or(Cycle::Wait)
-                    {
-                        command = commands.pop();
-                    }
+
+            Ok(computed_value.into())
+        }
+        PuzzlePart::Two => {
+            let width = parameters.get::<usize>(&SCREEN_WIDTH)?;
+            let height = parameters.get::<usize>(&SCREEN_HEIGHT)?;
+            let mut command = commands.next().transpose()?;
+            let mut screen = vec![Vec::default(); height];
+
+            for n in 0..width * height {
+                let x = (n % width) as i64;
+
+                if tracer.enabled() {
+                    tracer.trace(Event::Cycle {
+                        cycle: n + 1,
+                        x: register.clone(),
+                    })?;
                 }
 
-                Ok(result)
+                screen[n / width]
+                    .push(register == x.into() || register == (x - 1).into() || register == (x + 1).into());
+
+                if let Cycle::Done = command
+                    .as_mut()
+                    .map(|command| command.execute(&mut register))
+                    .unwrap_or(Cycle::Wait)
+                {
+                    command = commands.next().transpose()?;
+                }
             }
+
+            Ok(screen.into())
         }
     }
 }
+
+impl Solve for Solver {
+    fn solve_with(&self, parameters: &Parameters, tracer: &mut dyn Tracer) -> Result<Answer> {
+        run(&mut self.commands.iter().cloned().map(Ok), parameters, tracer)
+    }
+}
+
+/// Generates `size` instructions keeping the register X within the screen.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        let mut register = 1;
+
+        for _ in 0..size {
+            if rng.gen_bool(0.3) {
+                writeln!(writer, "noop")?;
+            } else {
+                let value = rng.gen_range(-10..=10).clamp(-register, 39 - register);
+
+                register += value;
+                writeln!(writer, "addx {value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,10 +1,86 @@
-pub(crate) mod day01;
-pub(crate) mod day02;
-pub(crate) mod day03;
-pub(crate) mod day04;
-pub(crate) mod day05;
-pub(crate) mod day06;
-pub(crate) mod day07;
-pub(crate) mod day08;
-pub(crate) mod day09;
-pub(crate) mod day10;
+//! Parsed models of the puzzles, one module per day.
+//!
+//! Each module exposes a `Solver` built from the puzzle input, giving access to the parsed model, the `PUZZLE`
+//! metadata and the `Generator` of random inputs.
+
+#[cfg(feature = "day01")]
+pub mod day01;
+#[cfg(feature = "day02")]
+pub mod day02;
+#[cfg(feature = "day03")]
+pub mod day03;
+#[cfg(feature = "day04")]
+pub mod day04;
+#[cfg(feature = "day05")]
+pub mod day05;
+#[cfg(feature = "day06")]
+pub mod day06;
+#[cfg(feature = "day07")]
+pub mod day07;
+#[cfg(feature = "day08")]
+pub mod day08;
+#[cfg(feature = "day09")]
+pub mod day09;
+#[cfg(feature = "day10")]
+pub mod day10;
+
+use crate::solver::Puzzle;
+
+pub(crate) const PUZZLES: &[Puzzle] = &[
+    #[cfg(feature = "day01")]
+    day01::PUZZLE,
+    #[cfg(feature = "day02")]
+    day02::PUZZLE,
+    #[cfg(feature = "day03")]
+    day03::PUZZLE,
+    #[cfg(feature = "day04")]
+    day04::PUZZLE,
+    #[cfg(feature = "day05")]
+    day05::PUZZLE,
+    #[cfg(feature = "day06")]
+    day06::PUZZLE,
+    #[cfg(feature = "day07")]
+    day07::PUZZLE,
+    #[cfg(feature = "day08")]
+    day08::PUZZLE,
+    #[cfg(feature = "day09")]
+    day09::PUZZLE,
+    #[cfg(feature = "day10")]
+    day10::PUZZLE,
+];
+
+/// Cargo feature of each implemented day, and whether it is enabled.
+const FEATURES: &[(&str, bool)] = &[
+    ("day01", cfg!(feature = "day01")),
+    ("day02", cfg!(feature = "day02")),
+    ("day03", cfg!(feature = "day03")),
+    ("day04", cfg!(feature = "day04")),
+    ("day05", cfg!(feature = "day05")),
+    ("day06", cfg!(feature = "day06")),
+    ("day07", cfg!(feature = "day07")),
+    ("day08", cfg!(feature = "day08")),
+    ("day09", cfg!(feature = "day09")),
+    ("day10", cfg!(feature = "day10")),
+];
+
+// an empty `Solver` could not be matched by reference
+#[cfg(not(any(
+    feature = "day01",
+    feature = "day02",
+    feature = "day03",
+    feature = "day04",
+    feature = "day05",
+    feature = "day06",
+    feature = "day07",
+    feature = "day08",
+    feature = "day09",
+    feature = "day10",
+)))]
+compile_error!("no day is compiled in: enable the cargo feature of at least one day, e.g. 'day01' or 'all-days'");
+
+/// Whether `day` is implemented but its cargo feature is disabled.
+pub(crate) fn is_compiled_out(day: u16) -> bool {
+    let feature = format!("day{day:02}");
+
+    FEATURES.iter().any(|(name, enabled)| *name == feature && !enabled)
+}
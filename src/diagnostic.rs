@@ -0,0 +1,193 @@
+use std::fmt;
+use std::ops::Range;
+
+use pest::error::LineColLocation;
+use pest::RuleType;
+
+/// Description of an invalid input, optionally located in its source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: Option<String>,
+    /// 1-based line number.
+    pub line: usize,
+    /// 0-based character columns highlighted in `source`.
+    pub span: Range<usize>,
+    /// Content of the line.
+    pub source: String,
+}
+
+impl Location {
+    /// 1-based column where the span starts.
+    pub fn column(&self) -> usize {
+        self.span.start + 1
+    }
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            location: None,
+        }
+    }
+
+    /// Locates the diagnostic of `fragment` in `source`, `fragment` being a sub-slice of `source`.
+    ///
+    /// An unlocated diagnostic spans the whole fragment while a located one keeps its span, shifted by the
+    /// position of `fragment` in `source`.
+    pub fn within(mut self, source: &str, fragment: &str) -> Self {
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(source.as_ptr() as usize)
+            .filter(|offset| offset + fragment.len() <= source.len())
+            .unwrap_or_default();
+        let shift = source[..offset].chars().count();
+
+        let span = match self.location.take() {
+            Some(location) => location.span.start + shift..location.span.end + shift,
+            None => shift..shift + fragment.chars().count(),
+        };
+
+        self.location = Some(Location {
+            file: None,
+            line: 1,
+            span,
+            source: source.to_string(),
+        });
+
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        if let Some(location) = self.location.as_mut() {
+            location.line = line;
+        }
+
+        self
+    }
+
+    pub fn in_file(mut self, file: impl Into<String>) -> Self {
+        if let Some(location) = self.location.as_mut() {
+            location.file = Some(file.into());
+        }
+
+        self
+    }
+}
+
+impl<R: RuleType> From<pest::error::Error<R>> for Diagnostic {
+    fn from(e: pest::error::Error<R>) -> Self {
+        // pest renders the line feed ending the line as '␊'
+        let source = e.line().trim_end_matches(['\r', '\n', '␊']).to_string();
+        let (line, span) = match e.line_col {
+            LineColLocation::Pos((line, column)) => (line, column - 1..column),
+            LineColLocation::Span((line, start), (end_line, end)) if line == end_line => (line, start - 1..end - 1),
+            LineColLocation::Span((line, start), _) => (line, start - 1..source.chars().count()),
+        };
+
+        Self {
+            message: e.variant.message().into_owned(),
+            location: Some(Location {
+                file: None,
+                line,
+                span,
+                source,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid input: {}", self.message)?;
+
+        if let Some(location) = &self.location {
+            let gutter = " ".repeat(location.line.to_string().len());
+            let file = location
+                .file
+                .as_ref()
+                .map(|file| format!("{file}:"))
+                .unwrap_or_default();
+
+            writeln!(f)?;
+            writeln!(f, "{gutter}--> {file}{}:{}", location.line, location.column())?;
+            writeln!(f, "{gutter} |")?;
+            writeln!(f, "{} | {}", location.line, location.source)?;
+            write!(
+                f,
+                "{gutter} | {}{}",
+                " ".repeat(location.span.start),
+                "^".repeat(location.span.len().max(1))
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_span_is_underlined() {
+        let source = "1a3";
+        let diagnostic = Diagnostic::new("wrong tree")
+            .within(source, &source[1..2])
+            .at_line(12)
+            .in_file("input");
+
+        assert_eq!(
+            diagnostic.to_string(),
+            "Invalid input: wrong tree\n  --> input:12:2\n   |\n12 | 1a3\n   |  ^"
+        );
+    }
+
+    #[test]
+    fn utf8_span_counts_characters() {
+        let source = "éè€ab";
+        let fragment = &source[source.find('a').unwrap()..];
+        let diagnostic = Diagnostic::new("wrong").within(source, fragment);
+
+        assert_eq!(diagnostic.location.as_ref().unwrap().span, 3..5);
+        assert_eq!(
+            diagnostic.to_string(),
+            "Invalid input: wrong\n --> 1:4\n  |\n1 | éè€ab\n  |    ^^"
+        );
+    }
+
+    #[test]
+    fn located_span_is_shifted_by_its_fragment() {
+        let source = "é: x€y";
+        let fragment = &source[source.find('x').unwrap()..];
+        let diagnostic = Diagnostic::new("wrong")
+            .within(fragment, &fragment[fragment.find('y').unwrap()..])
+            .within(source, fragment);
+
+        assert_eq!(diagnostic.location.unwrap().span, 5..6);
+    }
+
+    #[test]
+    fn span_at_end_of_line_has_a_caret() {
+        let source = "abc";
+        let diagnostic = Diagnostic::new("missing digit").within(source, &source[3..]);
+
+        assert_eq!(
+            diagnostic.to_string(),
+            "Invalid input: missing digit\n --> 1:4\n  |\n1 | abc\n  |    ^"
+        );
+    }
+
+    #[test]
+    fn unlocated_diagnostic_is_a_message() {
+        let diagnostic = Diagnostic::new("no elf").at_line(3).in_file("input");
+
+        assert_eq!(diagnostic.location, None);
+        assert_eq!(diagnostic.to_string(), "Invalid input: no elf");
+    }
+}
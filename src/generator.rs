@@ -0,0 +1,26 @@
+//! Generation of random valid puzzle inputs, e.g. to stress solvers with inputs much bigger than the
+//! official ones.
+
+use std::io::Write;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::solver::{Puzzle, Result};
+
+/// Generator of the input of a day, registered in `Puzzle::generator`.
+pub trait Generate {
+    /// Writes an input made of about `size` records (lines, rounds, moves, ...), whose meaning depends on the day.
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()>;
+}
+
+/// Writes the input of `day` generated from `seed`, the same seed always giving the same input.
+pub fn generate(day: u16, seed: u64, size: usize, writer: &mut dyn Write) -> Result<()> {
+    let puzzle = Puzzle::find(day)?;
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    puzzle.generator.generate(&mut rng, size, writer)?;
+    writer.flush()?;
+
+    Ok(())
+}
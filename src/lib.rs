@@ -1,4 +1,17 @@
 #![feature(result_flattening)]
 
-pub(crate) mod days;
+pub mod animate;
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod days;
+pub mod diagnostic;
+pub mod generator;
+pub mod normalize;
+pub mod number;
+pub mod repl;
+pub mod scaffold;
 pub mod solver;
+pub mod stream;
+pub mod trace;
+pub mod watch;
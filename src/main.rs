@@ -1,45 +1,810 @@
-use std::path::Path;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use advent_of_code_2022::solver::{PuzzlePart, Solve, Solver};
-use clap::{ArgGroup, Parser};
-use eyre::Result;
+use advent_of_code_2022::animate::{self, Player};
+use advent_of_code_2022::answer::Answer;
+use advent_of_code_2022::answers::{Answers, Outcome};
+use advent_of_code_2022::bench::{Baseline, Report, Sample};
+use advent_of_code_2022::normalize::Normalization;
+use advent_of_code_2022::scaffold::Scaffold;
+use advent_of_code_2022::solver::{Input, Parameters, Puzzle, PuzzlePart, Solver};
+use advent_of_code_2022::trace::{JsonLinesTracer, NoTracer, Tracer};
+use advent_of_code_2022::watch::Watcher;
+use advent_of_code_2022::{generator, repl};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use eyre::{bail, eyre, Result};
+use itertools::Itertools;
+use serde::Serialize;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
-#[command(group(ArgGroup::new("input").args(["example", "file"])))]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Opt {
-    /// Use user file as puzzle input
+    #[command(flatten)]
+    input: InputOpt,
+    /// Solve only one part of the puzzle, both parts being solved by default
+    #[arg(short, long)]
+    puzzle_part: Option<PuzzlePart>,
+    /// Indicates the puzzle to solve by its referencing day
+    #[arg(short, long, required_unless_present = "all")]
+    day: Option<u16>,
+    /// Solve every implemented day and print a results table
+    #[arg(short, long, conflicts_with_all = ["file", "day"])]
+    all: bool,
+    /// Restricts `--all` to a range of days (e.g. `3..7`, `3..=7` or `3`)
+    #[arg(long, requires = "all", value_parser = parse_days)]
+    days: Option<RangeInclusive<u16>>,
+    /// Output format of answers and errors
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Compare answers with the recorded ones and fail on mismatches
+    #[arg(short, long)]
+    check: bool,
+    /// Record answers of inputs which do not have one yet
+    #[arg(short, long, conflicts_with = "check")]
+    record: bool,
+    /// Manifest of the recorded answers
+    #[arg(long, default_value = "./data/answers")]
+    answers: PathBuf,
+    /// Keep running and solve again whenever the input file changes
+    #[arg(short, long, conflicts_with_all = ["all", "check", "record", "format"])]
+    watch: bool,
+    /// Delay between two checks of the watched input, in milliseconds
+    #[arg(long, requires = "watch", default_value_t = 500)]
+    poll_interval: u64,
+    /// Write the steps of the solvers into a file, as line-delimited JSON records
+    #[arg(long, conflicts_with = "watch")]
+    trace: Option<PathBuf>,
+    /// Replay the simulation of days 5, 9 and 10 in the terminal, controlled by lines typed on the standard input
+    /// (`p` pause/resume, `s` or enter step, `q` skip)
+    #[arg(long, conflicts_with_all = ["all", "watch", "trace", "format"])]
+    animate: bool,
+    /// Solve days 1, 2, 3, 4, 6, 9 and 10 while reading their input, without keeping it in memory (each part
+    /// reading the input again)
+    #[arg(long, conflicts_with_all = ["watch", "trace", "animate"])]
+    stream: bool,
+    /// Override a parameter of the puzzle (see the `params` subcommand), its default value depending on the puzzle
+    /// part
+    #[arg(long = "param", value_name = "NAME=VALUE", conflicts_with_all = ["all", "check", "record", "animate"])]
+    params: Vec<String>,
+    /// Frames per second of the animation
+    #[arg(long, requires = "animate", default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    frame_rate: u32,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("input").args(["example", "file"])))]
+pub struct InputOpt {
+    /// Use user file as puzzle input, `-` reading it from the standard input
     #[arg(short, long)]
     file: Option<String>,
     /// Use puzzle examples as input
     #[arg(short, long)]
     example: Option<Option<u16>>,
+    /// Parse the input as is, without normalizing line endings, trailing whitespace and the final newline
+    #[arg(long)]
+    strict: bool,
+}
+
+impl InputOpt {
+    fn input_file(&self, day: u16) -> String {
+        self.file.clone().unwrap_or_else(|| {
+            let prefix = if let Some(example) = self.example {
+                format!("example{}", example.unwrap_or(1))
+            } else {
+                "input".to_string()
+            };
+
+            format!("./data/{}_day{:0width$}", prefix, day, width = 2)
+        })
+    }
+
+    fn normalization(&self) -> Normalization {
+        if self.strict {
+            Normalization::Strict
+        } else {
+            Normalization::Tolerant
+        }
+    }
+
+    fn input(&self, day: u16) -> Result<Input> {
+        let input = match self.input_file(day).as_str() {
+            "-" => Input::from_stdin()?,
+            file => Input::file(file),
+        };
+
+        Ok(input.with_normalization(self.normalization()))
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Measure parsing and solving times of a puzzle
+    Bench(BenchOpt),
+    /// List the implemented puzzles and their fixtures
+    List,
+    /// Create the solver module and the empty fixtures of a new day
+    Scaffold(ScaffoldOpt),
+    /// Generate a random valid input of a puzzle
+    Generate(GenerateOpt),
+    /// Parse an input once, then answer the queries typed on the standard input
+    Repl(ReplOpt),
+    /// List the parameters of a puzzle, with their default value for each puzzle part
+    Params(ParamsOpt),
+    /// Parse a whole input and report all of its malformed records at once, instead of stopping at the first one
+    Validate(ValidateOpt),
+}
+
+#[derive(Args)]
+pub struct BenchOpt {
+    #[command(flatten)]
+    input: InputOpt,
     #[arg(short, long)]
     puzzle_part: PuzzlePart,
-    /// Indicates the puzzle to solve by its referencing day
+    /// Indicates the puzzle to bench by its referencing day
+    #[arg(short, long)]
+    day: u16,
+    /// Number of measured iterations
+    #[arg(short = 'n', long, default_value_t = 100)]
+    iterations: usize,
+    /// Number of unmeasured iterations run beforehand
+    #[arg(short, long, default_value_t = 10)]
+    warmup: usize,
+    /// Compare medians against a baseline file and fail on regressions
+    #[arg(short, long)]
+    baseline: Option<PathBuf>,
+    /// Save results into a baseline file, keeping entries of other days and parts
+    #[arg(short, long)]
+    save_baseline: Option<PathBuf>,
+    /// Slowdown of a median, in percent, above which a phase is flagged as regressing
+    #[arg(short, long, default_value_t = 10.0)]
+    threshold: f64,
+}
+
+#[derive(Args)]
+pub struct ScaffoldOpt {
+    /// Indicates the puzzle to create by its referencing day
+    #[arg(short, long)]
+    day: u16,
+    /// Parse the input with a pest grammar
+    #[arg(long)]
+    pest: bool,
+}
+
+#[derive(Args)]
+pub struct GenerateOpt {
+    /// Indicates the puzzle to generate an input for by its referencing day
+    #[arg(short, long)]
+    day: u16,
+    /// Seed of the random generator, the same seed always giving the same input
+    #[arg(short, long, default_value_t = 0)]
+    seed: u64,
+    /// Number of records of the input (lines, rounds, moves, ...), depending on the day
+    #[arg(short = 'k', long, default_value_t = 1000)]
+    size: usize,
+    /// Write the input into a file instead of the standard output
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct ReplOpt {
+    #[command(flatten)]
+    input: InputOpt,
+    /// Indicates the puzzle to explore by its referencing day
+    #[arg(short, long)]
+    day: u16,
+}
+
+#[derive(Args)]
+pub struct ParamsOpt {
+    /// Indicates the puzzle whose parameters are listed by its referencing day
+    #[arg(short, long)]
+    day: u16,
+}
+
+#[derive(Args)]
+pub struct ValidateOpt {
+    #[command(flatten)]
+    input: InputOpt,
+    /// Indicates the puzzle whose input is validated by its referencing day
     #[arg(short, long)]
     day: u16,
 }
 
+fn parse_days(s: &str) -> Result<RangeInclusive<u16>, String> {
+    let parse = |s: &str| {
+        s.parse::<u16>()
+            .map_err(|e| format!("day must be a valid unsigned integer (got '{s}'): {e}"))
+    };
+
+    if let Some((start, end)) = s.split_once("..=") {
+        Ok(parse(start)?..=parse(end)?)
+    } else if let Some((start, end)) = s.split_once("..") {
+        let end = parse(end)?
+            .checked_sub(1)
+            .ok_or_else(|| format!("empty range of days '{s}'"))?;
+
+        Ok(parse(start)?..=end)
+    } else {
+        let day = parse(s)?;
+
+        Ok(day..=day)
+    }
+}
+
+struct Run {
+    day: u16,
+    puzzle_part: PuzzlePart,
+    input: String,
+    file_name: Option<String>,
+    sample: Sample,
+}
+
+impl Run {
+    /// Solves with each of `parameters`, one per puzzle part, from a single parsing of the input.
+    fn for_parts(input: &Input, day: u16, parameters: &[Parameters], tracer: &mut dyn Tracer) -> Vec<Self> {
+        parameters
+            .iter()
+            .zip(Sample::measure_parts(input, day, parameters, tracer))
+            .map(|(parameters, sample)| {
+                Self {
+                    day,
+                    puzzle_part: parameters.puzzle_part(),
+                    input: input.to_string(),
+                    file_name: input.file_name().map(str::to_string),
+                    sample,
+                }
+            })
+            .collect()
+    }
+
+    /// Solves with each of `parameters`, one per puzzle part, while reading the input once per part.
+    fn streaming(input: &Input, day: u16, parameters: &[Parameters]) -> Vec<Self> {
+        parameters
+            .iter()
+            .map(|parameters| {
+                Self {
+                    day,
+                    puzzle_part: parameters.puzzle_part(),
+                    input: input.to_string(),
+                    file_name: input.file_name().map(str::to_string),
+                    sample: Sample::measure_streaming(input, day, parameters),
+                }
+            })
+            .collect()
+    }
+
+    fn file_name(&self) -> Result<&str> {
+        self.file_name
+            .as_deref()
+            .ok_or_else(|| eyre!("answers of an input read from the standard input cannot be recorded"))
+    }
+
+    fn to_record(&self) -> RunRecord<'_> {
+        let (answer, error) = match &self.sample.solution {
+            Ok(answer) => (Some(answer), None),
+            Err(e) => {
+                (
+                    None,
+                    Some(ErrorRecord {
+                        variant: e.kind(),
+                        message: format!("{e:#}"),
+                    }),
+                )
+            }
+        };
+
+        RunRecord {
+            day: self.day,
+            part: self.puzzle_part.to_string(),
+            input: &self.input,
+            answer,
+            timings: TimingsRecord {
+                parse_ns: self.sample.parse_time.map(|parse_time| parse_time.as_nanos()),
+                solve_ns: self.sample.solve_time.map(|solve_time| solve_time.as_nanos()),
+            },
+            error,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct RunRecord<'a> {
+    day: u16,
+    part: String,
+    input: &'a str,
+    answer: Option<&'a Answer>,
+    timings: TimingsRecord,
+    error: Option<ErrorRecord>,
+}
+
+#[derive(Serialize)]
+struct TimingsRecord {
+    parse_ns: Option<u128>,
+    solve_ns: Option<u128>,
+}
+
+#[derive(Serialize)]
+struct ErrorRecord {
+    variant: &'static str,
+    message: String,
+}
+
+fn print_runs(runs: &[Run]) {
+    let rows = runs
+        .iter()
+        .map(|run| {
+            [
+                run.day.to_string(),
+                run.puzzle_part.to_string(),
+                match &run.sample.solution {
+                    Ok(answer) => answer.to_string(),
+                    Err(e) => format!("error: {e}"),
+                },
+                run.sample
+                    .parse_time
+                    .map(|parse_time| format!("{parse_time:.2?}"))
+                    .unwrap_or_else(|| "-".to_string()),
+                run.sample
+                    .solve_time
+                    .map(|solve_time| format!("{solve_time:.2?}"))
+                    .unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    print_table(["day", "part", "answer", "parse", "solve"], &rows);
+}
+
+fn print_table<const N: usize>(headers: [&str; N], rows: &[[String; N]]) {
+    let widths = headers.map(str::len);
+    let widths = rows.iter().fold(widths, |mut widths, row| {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = cell.lines().map(|line| line.chars().count()).fold(*width, usize::max);
+        }
+
+        widths
+    });
+
+    let print_line = |cells: [&str; N]| {
+        println!(
+            "{}",
+            cells
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .join(" | ")
+                .trim_end()
+        );
+    };
+
+    print_line(headers);
+    println!("{}", widths.map(|width| "-".repeat(width)).join("-+-"));
+
+    for row in rows.iter() {
+        // multi-line cells (e.g. CRT images or parser reports) span several table lines
+        let height = row.iter().map(|cell| cell.lines().count()).max().unwrap_or(1);
+
+        for i in 0..height {
+            print_line(row.each_ref().map(|cell| cell.lines().nth(i).unwrap_or_default()));
+        }
+    }
+}
+
+fn list() {
+    let rows = Solver::PUZZLES
+        .iter()
+        .map(|puzzle| {
+            let examples = (1..)
+                .take_while(|n| Path::new(&format!("./data/example{n}_day{:02}", puzzle.day)).exists())
+                .count();
+            let input = Path::new(&format!("./data/input_day{:02}", puzzle.day)).exists();
+
+            [
+                puzzle.day.to_string(),
+                puzzle.title.to_string(),
+                puzzle.parts.iter().join(", "),
+                puzzle
+                    .parameters
+                    .iter()
+                    .map(|parameter| format!("{}={}", parameter.name, parameter.defaults.iter().dedup().join("/")))
+                    .join("\n"),
+                examples.to_string(),
+                if input { "yes" } else { "no" }.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    print_table(["day", "title", "parts", "parameters", "examples", "input"], &rows);
+}
+
+fn params(puzzle: &Puzzle) {
+    let rows = puzzle
+        .parameters
+        .iter()
+        .map(|parameter| {
+            [
+                parameter.name.to_string(),
+                parameter.default_value(PuzzlePart::One).to_string(),
+                parameter.default_value(PuzzlePart::Two).to_string(),
+                parameter.kind.to_string(),
+                parameter.description.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    if rows.is_empty() {
+        println!("day {} has no parameter", puzzle.day);
+    } else {
+        print_table(
+            ["name", "part one", "part two", "accepted values", "description"],
+            &rows,
+        );
+    }
+}
+
+fn validate(input: &Input, day: u16) -> Result<()> {
+    let errors = input.validate(day)?;
+
+    for error in &errors {
+        println!("{error}\n");
+    }
+
+    if !errors.is_empty() {
+        bail!("{} error(s) in '{input}' for day {day}", errors.len());
+    }
+
+    println!("'{input}' is a valid input for day {day}");
+
+    Ok(())
+}
+
+fn bench(opt: BenchOpt) -> Result<()> {
+    let input = opt.input.input(opt.day)?;
+    let report = Report::run(&input, opt.day, opt.puzzle_part, opt.warmup, opt.iterations)?;
+
+    println!(
+        "day {} part {} ({} iterations)",
+        report.day, report.puzzle_part, report.iterations
+    );
+    println!("parse: {}", report.parse);
+    println!("solve: {}", report.solve);
+
+    let regressions = opt
+        .baseline
+        .as_deref()
+        .map(Baseline::load)
+        .transpose()?
+        .map(|baseline| baseline.regressions(&report, opt.threshold))
+        .unwrap_or_default();
+
+    if let Some(path) = opt.save_baseline.as_deref() {
+        let mut baseline = if path.exists() {
+            Baseline::load(path)?
+        } else {
+            Baseline::default()
+        };
+
+        baseline.insert(&report);
+        baseline.save(path)?;
+    }
+
+    if !regressions.is_empty() {
+        for regression in regressions.iter() {
+            eprintln!("regression: {regression}");
+        }
+
+        bail!(
+            "{} phase(s) regressed by more than {}%",
+            regressions.len(),
+            opt.threshold
+        );
+    }
+
+    Ok(())
+}
+
+fn watch(input: &InputOpt, day: u16, parameters: &[Parameters], poll_interval: Duration) -> Result<()> {
+    let path = input.input_file(day);
+
+    if path == "-" {
+        bail!("the standard input cannot be watched");
+    }
+
+    let input = Input::file(path.clone()).with_normalization(input.normalization());
+    let mut watcher = Watcher::new(path, poll_interval);
+    let mut previous_runs: Vec<Run> = Vec::default();
+
+    loop {
+        let runs = Run::for_parts(&input, day, parameters, &mut NoTracer);
+
+        print_runs(&runs);
+
+        for (previous_run, run) in previous_runs.iter().zip(runs.iter()) {
+            let (previous, actual) = (
+                Outcome::from_solution(&previous_run.sample.solution),
+                Outcome::from_solution(&run.sample.solution),
+            );
+
+            if previous != actual {
+                println!("part {} changed\n{}", run.puzzle_part, previous.diff(&actual));
+            }
+        }
+
+        previous_runs = runs;
+
+        eprintln!("watching '{}' for changes...", watcher.path().display());
+        watcher.wait_for_change();
+        println!();
+    }
+}
+
+/// Returns the number of runs whose answer is missing from or differs from the manifest.
+fn check(path: &Path, runs: &[Run]) -> Result<usize> {
+    let answers = Answers::load(path)?;
+    let mut mismatches = 0;
+
+    for run in runs.iter() {
+        let file_name = run.file_name()?;
+        let actual = Outcome::from_solution(&run.sample.solution);
+
+        match answers.get(file_name, run.day, run.puzzle_part) {
+            Some(expected) if *expected == actual => continue,
+            Some(Outcome::Pending) | None => {
+                eprintln!(
+                    "day {} part {}: no recorded answer for '{file_name}'",
+                    run.day, run.puzzle_part
+                )
+            }
+            Some(expected) => {
+                eprintln!(
+                    "day {} part {}: answer for '{file_name}' differs from the recorded one\n{}",
+                    run.day,
+                    run.puzzle_part,
+                    expected.diff(&actual)
+                );
+            }
+        }
+
+        mismatches += 1;
+    }
+
+    Ok(mismatches)
+}
+
+fn record(path: &Path, runs: &[Run]) -> Result<()> {
+    let mut answers = Answers::load(path)?;
+    let mut recorded = 0;
+
+    for run in runs.iter() {
+        if answers.insert(
+            run.file_name()?,
+            run.day,
+            run.puzzle_part,
+            Outcome::from_solution(&run.sample.solution),
+        ) {
+            recorded += 1;
+        }
+    }
+
+    answers.save(path)?;
+
+    eprintln!("{recorded} new answer(s) recorded in '{}'", path.display());
+
+    Ok(())
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
 
     let opt = Opt::parse();
 
-    let file = opt.file.unwrap_or_else(|| {
-        let prefix = if let Some(example) = opt.example {
-            format!("example{}", example.unwrap_or(1))
+    match opt.command {
+        Some(Command::Bench(bench_opt)) => return bench(bench_opt),
+        Some(Command::List) => {
+            list();
+
+            return Ok(());
+        }
+        Some(Command::Validate(validate_opt)) => {
+            return validate(&validate_opt.input.input(validate_opt.day)?, validate_opt.day);
+        }
+        Some(Command::Params(params_opt)) => {
+            params(Puzzle::find(params_opt.day)?);
+
+            return Ok(());
+        }
+        Some(Command::Generate(generate_opt)) => {
+            let mut writer: Box<dyn Write> = match &generate_opt.output {
+                Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+                None => Box::new(BufWriter::new(io::stdout().lock())),
+            };
+
+            generator::generate(generate_opt.day, generate_opt.seed, generate_opt.size, &mut writer)?;
+
+            return Ok(());
+        }
+        Some(Command::Repl(repl_opt)) => {
+            if repl_opt.input.input_file(repl_opt.day) == "-" {
+                bail!("the standard input cannot be both the puzzle input and the REPL input");
+            }
+
+            let solver = repl_opt.input.input(repl_opt.day)?.parse(repl_opt.day)?;
+
+            repl::run(
+                Puzzle::find(repl_opt.day)?,
+                &solver,
+                io::stdin().lock(),
+                io::stdout().lock(),
+            )?;
+
+            return Ok(());
+        }
+        Some(Command::Scaffold(scaffold_opt)) => {
+            let scaffold = Scaffold {
+                day: scaffold_opt.day,
+                pest: scaffold_opt.pest,
+            };
+
+            for path in scaffold.run(Path::new("."))? {
+                println!("created {}", path.display());
+            }
+
+            return Ok(());
+        }
+        None => {}
+    }
+
+    let puzzle_parts = match opt.puzzle_part {
+        Some(puzzle_part) => vec![puzzle_part],
+        None => vec![PuzzlePart::One, PuzzlePart::Two],
+    };
+    let mut parameters = puzzle_parts.iter().copied().map(Parameters::preset).collect::<Vec<_>>();
+
+    if !opt.params.is_empty() {
+        // required by clap unless `--all` is present, which conflicts with `--param`
+        let puzzle = Puzzle::find(opt.day.unwrap())?;
+
+        parameters = parameters
+            .into_iter()
+            .map(|parameters| parameters.with_assignments(puzzle, &opt.params))
+            .collect::<Result<_, _>>()?;
+    }
+
+    if opt.watch {
+        // required by clap unless `--all` is present, which conflicts with `--watch`
+        return watch(
+            &opt.input,
+            opt.day.unwrap(),
+            &parameters,
+            Duration::from_millis(opt.poll_interval),
+        );
+    }
+
+    let mut trace_file = opt
+        .trace
+        .as_deref()
+        .map(|path| {
+            File::create(path)
+                .map(|file| JsonLinesTracer::new(BufWriter::new(file)))
+                .map_err(|e| eyre!("Cannot create trace file '{}': {e}", path.display()))
+        })
+        .transpose()?;
+    let mut player = if opt.animate {
+        // required by clap unless `--all` is present, which conflicts with `--animate`
+        let day = opt.day.unwrap();
+
+        if !animate::ANIMATED_DAYS.contains(&day) {
+            bail!(
+                "day {day} cannot be animated (animated days: {})",
+                animate::ANIMATED_DAYS.iter().join(", ")
+            );
+        }
+
+        Some(Player::new(io::stdout(), opt.frame_rate))
+    } else {
+        None
+    };
+    let tracer: &mut dyn Tracer = match (trace_file.as_mut(), player.as_mut()) {
+        (Some(tracer), _) => tracer,
+        (None, Some(player)) => player,
+        (None, None) => &mut NoTracer,
+    };
+
+    let runs = if opt.all {
+        Solver::PUZZLES
+            .iter()
+            // only the days able to stream their input are solved when streaming
+            .filter(|puzzle| !opt.stream || puzzle.stream.is_some())
+            .map(|puzzle| puzzle.day)
+            .filter(|day| opt.days.as_ref().is_none_or(|days| days.contains(day)))
+            .flat_map(|day| {
+                let input = Input::file(opt.input.input_file(day)).with_normalization(opt.input.normalization());
+
+                if opt.stream {
+                    Run::streaming(&input, day, &parameters)
+                } else {
+                    Run::for_parts(&input, day, &parameters, tracer)
+                }
+            })
+            .collect::<Vec<_>>()
+    } else {
+        // required by clap unless `--all` is present
+        let day = opt.day.unwrap();
+        let input = opt.input.input(day)?;
+
+        if opt.stream {
+            Run::streaming(&input, day, &parameters)
         } else {
-            "input".to_string()
-        };
+            Run::for_parts(&input, day, &parameters, tracer)
+        }
+    };
 
-        format!("./data/{}_day{:0width$}", prefix, opt.day, width = 2)
-    });
+    if let Some(trace_file) = trace_file {
+        trace_file.finish()?;
+    }
+
+    if opt.record {
+        record(&opt.answers, &runs)?;
+    }
+
+    let mismatches = if opt.check { check(&opt.answers, &runs)? } else { 0 };
+
+    // an error may be the expected outcome when checking answers
+    let failures = if opt.check || opt.record {
+        0
+    } else {
+        runs.iter().filter(|run| run.sample.solution.is_err()).count()
+    };
+
+    match opt.format {
+        // every run is listed, the failed ones included, before failing
+        Format::Text if opt.all || runs.len() > 1 => {
+            print_runs(&runs);
+
+            if failures > 0 {
+                bail!("{failures} puzzle part(s) failed");
+            }
+        }
+        Format::Text => {
+            match runs.into_iter().next().unwrap().sample.solution {
+                Ok(solution) => println!("{solution}"),
+                // an error may be the expected outcome when checking answers
+                Err(e) if opt.check || opt.record => println!("error: {e}"),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Format::Json => {
+            if let (false, [run]) = (opt.all, runs.as_slice()) {
+                println!("{}", serde_json::to_string_pretty(&run.to_record())?);
+            } else {
+                let records = runs.iter().map(Run::to_record).collect::<Vec<_>>();
+
+                println!("{}", serde_json::to_string_pretty(&records)?);
+            }
 
-    let solver = Solver::from_file(Path::new(&file), opt.day)?;
-    let solution = solver.solve(opt.puzzle_part)?;
+            // errors are already reported in the JSON records
+            if failures > 0 {
+                std::process::exit(1);
+            }
+        }
+    }
 
-    println!("{solution}");
+    if mismatches > 0 {
+        bail!("{mismatches} answer(s) do not match '{}'", opt.answers.display());
+    }
 
     Ok(())
 }
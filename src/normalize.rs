@@ -0,0 +1,58 @@
+//! Normalization of puzzle inputs, so that the text files of any editor or platform parse alike.
+
+use std::io::{self, BufRead, Read};
+
+/// How an input is preprocessed before being parsed.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Normalization {
+    /// Ends every line, the last one included, with a single line feed and without trailing whitespace.
+    #[default]
+    Tolerant,
+    /// Reads the input as is.
+    Strict,
+}
+
+/// Reads an input one normalized line at a time, see [`Normalization::Tolerant`].
+///
+/// Carriage returns being trailing whitespace, CRLF line endings become line feeds. Whitespace-only lines become
+/// empty lines, and an empty input stays empty.
+pub struct Normalizer<R: BufRead> {
+    reader: R,
+    line: Vec<u8>,
+    position: usize,
+}
+
+impl<R: BufRead> Normalizer<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: Vec::default(),
+            position: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Read for Normalizer<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position == self.line.len() {
+            self.line.clear();
+            self.position = 0;
+
+            if self.reader.read_until(b'\n', &mut self.line)? == 0 {
+                return Ok(0);
+            }
+
+            let length = self.line.trim_ascii_end().len();
+
+            self.line.truncate(length);
+            self.line.push(b'\n');
+        }
+
+        let length = buf.len().min(self.line.len() - self.position);
+
+        buf[..length].copy_from_slice(&self.line[self.position..self.position + length]);
+        self.position += length;
+
+        Ok(length)
+    }
+}
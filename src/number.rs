@@ -0,0 +1,283 @@
+//! Integers computed with machine integers, switching to big integers only when an operation overflows.
+//!
+//! Values fitting a machine integer are always stored as one, so that equality and hashing compare values
+//! whatever the operations which produced them.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+use num_bigint::{BigInt, BigUint, ParseBigIntError};
+use num_traits::Zero;
+use serde::{Serialize, Serializer};
+
+use crate::answer::Answer;
+
+macro_rules! checked_number {
+    ($(#[$meta:meta])* $name:ident($small:ty, $big:ty): $($from:ty),+) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            Small($small),
+            Big($big),
+        }
+
+        impl $name {
+            /// Stores `n` as a machine integer when it fits one.
+            fn normalize(n: $big) -> Self {
+                match <$small>::try_from(&n) {
+                    Ok(n) => Self::Small(n),
+                    Err(_) => Self::Big(n),
+                }
+            }
+
+            /// Value as a machine integer, `None` when it does not fit one.
+            pub fn to_small(&self) -> Option<$small> {
+                match self {
+                    Self::Small(n) => Some(*n),
+                    Self::Big(_) => None,
+                }
+            }
+
+            pub fn to_big(&self) -> $big {
+                match self {
+                    Self::Small(n) => <$big>::from(*n),
+                    Self::Big(n) => n.clone(),
+                }
+            }
+
+            /// Applies `checked` on machine integers, falling back to `big` on overflow.
+            fn operate(
+                &self,
+                other: &Self,
+                checked: fn($small, $small) -> Option<$small>,
+                big: fn($big, $big) -> $big,
+            ) -> Self {
+                match (self, other) {
+                    (Self::Small(a), Self::Small(b)) => {
+                        checked(*a, *b)
+                            .map(Self::Small)
+                            .unwrap_or_else(|| Self::normalize(big(<$big>::from(*a), <$big>::from(*b))))
+                    }
+                    (a, b) => Self::normalize(big(a.to_big(), b.to_big())),
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::Small(0)
+            }
+        }
+
+        impl Zero for $name {
+            fn zero() -> Self {
+                Self::default()
+            }
+
+            fn is_zero(&self) -> bool {
+                *self == Self::Small(0)
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                match (self, other) {
+                    (Self::Small(a), Self::Small(b)) => a.cmp(b),
+                    (a, b) => a.to_big().cmp(&b.to_big()),
+                }
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Self::Small(n) => write!(f, "{n}"),
+                    Self::Big(n) => write!(f, "{n}"),
+                }
+            }
+        }
+
+        /// Serialized as a string of digits, as big integers in [`Answer`].
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseBigIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.parse::<$small>() {
+                    Ok(n) => Ok(Self::Small(n)),
+                    // either too big or invalid, which the big integer tells apart
+                    Err(_) => s.parse::<$big>().map(Self::normalize),
+                }
+            }
+        }
+
+        $(
+            impl From<$from> for $name {
+                fn from(n: $from) -> Self {
+                    Self::Small(n as _)
+                }
+            }
+        )+
+
+        impl From<&$name> for $name {
+            fn from(n: &$name) -> Self {
+                n.clone()
+            }
+        }
+
+        impl From<$big> for $name {
+            fn from(n: $big) -> Self {
+                Self::normalize(n)
+            }
+        }
+
+        impl From<$name> for $big {
+            fn from(n: $name) -> Self {
+                match n {
+                    $name::Small(n) => n.into(),
+                    $name::Big(n) => n,
+                }
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), |acc, n| acc + n)
+            }
+        }
+
+        impl<'a> Sum<&'a $name> for $name {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), |acc, n| acc + n)
+            }
+        }
+
+        checked_number!(@operator $name($small, $big): Add add AddAssign add_assign checked_add);
+        checked_number!(@operator $name($small, $big): Sub sub SubAssign sub_assign checked_sub);
+        checked_number!(@operator $name($small, $big): Mul mul MulAssign mul_assign checked_mul);
+    };
+    (@operator $name:ident($small:ty, $big:ty): $trait:ident $method:ident $assign_trait:ident $assign_method:ident $checked:ident) => {
+        impl<T: Into<$name>> $trait<T> for $name {
+            type Output = Self;
+
+            fn $method(self, other: T) -> Self {
+                self.operate(&other.into(), <$small>::$checked, |a, b| a.$method(b))
+            }
+        }
+
+        impl<T: Into<$name>> $trait<T> for &$name {
+            type Output = $name;
+
+            fn $method(self, other: T) -> $name {
+                self.operate(&other.into(), <$small>::$checked, |a, b| a.$method(b))
+            }
+        }
+
+        impl<T: Into<$name>> $assign_trait<T> for $name {
+            fn $assign_method(&mut self, other: T) {
+                *self = self.operate(&other.into(), <$small>::$checked, |a, b| a.$method(b));
+            }
+        }
+    };
+}
+
+checked_number!(
+    /// Unsigned integer computed as a `u64` until it overflows, then as a [`BigUint`].
+    ///
+    /// As with [`BigUint`], subtracting a greater value panics.
+    Natural(u64, BigUint): u8, u16, u32, u64, usize
+);
+
+checked_number!(
+    /// Signed integer computed as an `i64` until it overflows, then as a [`BigInt`].
+    Integer(i64, BigInt): i8, i16, i32, i64, u8, u16, u32
+);
+
+impl From<Natural> for Answer {
+    fn from(n: Natural) -> Self {
+        match n {
+            Natural::Small(n) => Self::Unsigned(n),
+            Natural::Big(n) => Self::BigUnsigned(n),
+        }
+    }
+}
+
+impl From<Integer> for Answer {
+    fn from(n: Integer) -> Self {
+        match n {
+            Integer::Small(n) => Self::Signed(n),
+            Integer::Big(n) => Self::BigSigned(n),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow_falls_back_to_big_integers() {
+        let natural = Natural::from(u64::MAX) + 1u8;
+
+        assert_eq!(natural, Natural::Big(BigUint::from(u64::MAX) + 1u8));
+        assert_eq!(natural.to_small(), None);
+        assert_eq!(natural.to_string(), "18446744073709551616");
+
+        let integer = Integer::from(i64::MIN) * -1i8;
+
+        assert_eq!(integer, Integer::Big(-BigInt::from(i64::MIN)));
+        assert_eq!(Integer::from(i64::MIN) - 1u8, Integer::Big(BigInt::from(i64::MIN) - 1));
+    }
+
+    #[test]
+    fn big_results_fitting_machine_integers_become_small() {
+        assert_eq!(Natural::from(u64::MAX) + 1u8 - 1u8, Natural::Small(u64::MAX));
+        assert_eq!((Integer::from(i64::MAX) + 1u8) * 0u8, Integer::Small(0));
+        assert_eq!(Natural::from(BigUint::from(42u8)), Natural::Small(42));
+        assert_eq!("-9223372036854775808".parse::<Integer>(), Ok(Integer::Small(i64::MIN)));
+        assert_eq!(
+            "18446744073709551616".parse::<Natural>(),
+            Ok(Natural::Big(BigUint::from(u64::MAX) + 1u8))
+        );
+        assert!("x".parse::<Natural>().is_err());
+    }
+
+    #[test]
+    fn big_and_small_integers_compare_by_value() {
+        let big = Natural::from(u64::MAX) + 1u8;
+
+        assert!(Natural::Small(u64::MAX) < big);
+        assert_eq!(big.clone() - 1u8, Natural::from(u64::MAX));
+        assert_eq!(
+            [big.clone(), Natural::from(3u8)].iter().max(),
+            Some(&big),
+            "max of a big and a small natural"
+        );
+
+        let negative = Integer::from(i64::MIN) - 1u8;
+
+        assert!(negative < Integer::from(i64::MIN));
+        assert!(Integer::from(-1i8) < Integer::zero());
+        assert!((Integer::from(i64::MAX) + 1u8) > Integer::from(i64::MAX));
+    }
+
+    #[test]
+    #[should_panic]
+    fn natural_subtraction_underflow_panics() {
+        let _ = Natural::from(1u8) - 2u8;
+    }
+}
@@ -0,0 +1,139 @@
+//! Interactive exploration of a parsed input, one query per line.
+
+use std::io::{BufRead, Write};
+
+use itertools::Itertools;
+
+use crate::solver::{Error, Puzzle, PuzzlePart, Result, Solve, Solver};
+
+/// Commands available whatever the day, with their arguments and description.
+const COMMANDS: &[(&str, &str, &str)] = &[
+    ("solve", "part", "answer of the puzzle part one or two"),
+    ("help", "", "list the available commands"),
+    ("quit", "", "leave the REPL"),
+];
+
+/// Reads commands from `reader` until its end or `quit`, answering the queries of `puzzle` with `solver`.
+///
+/// Errors of a command are printed and do not stop the REPL.
+pub fn run(puzzle: &Puzzle, solver: &Solver, reader: impl BufRead, mut writer: impl Write) -> Result<()> {
+    let prompt = format!("day {:02}> ", puzzle.day);
+
+    writeln!(
+        writer,
+        "day {} ({}): input parsed, type 'help' to list the commands",
+        puzzle.day, puzzle.title
+    )?;
+    write!(writer, "{prompt}")?;
+    writer.flush()?;
+
+    for line in reader.lines() {
+        let line = line?;
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+
+        match tokens.as_slice() {
+            [] => {}
+            ["quit" | "exit"] => return Ok(()),
+            ["help"] => writeln!(writer, "{}", help(puzzle))?,
+            [name, arguments @ ..] => {
+                match answer(puzzle, solver, name, arguments) {
+                    Ok(answer) => writeln!(writer, "{answer}")?,
+                    Err(e) => writeln!(writer, "error: {e}")?,
+                }
+            }
+        }
+
+        write!(writer, "{prompt}")?;
+        writer.flush()?;
+    }
+
+    writeln!(writer)?;
+
+    Ok(())
+}
+
+fn answer(puzzle: &Puzzle, solver: &Solver, name: &str, arguments: &[&str]) -> Result<String> {
+    if name == "solve" {
+        let puzzle_part = match arguments {
+            [part] => {
+                part.parse::<PuzzlePart>()
+                    .map_err(|_| Error::invalid_input(format!("solve: part must be one or two (got '{part}')")))?
+            }
+            _ => return Err(Error::invalid_input("usage: solve part")),
+        };
+
+        return Ok(solver.solve(puzzle_part)?.to_string());
+    }
+
+    let query =
+        puzzle.queries.iter().find(|query| query.name == name).ok_or_else(|| {
+            Error::invalid_input(format!("unknown command '{name}', type 'help' to list the commands"))
+        })?;
+
+    if arguments.len() != query.arity() {
+        return Err(Error::invalid_input(format!(
+            "usage: {} {}",
+            query.name, query.arguments
+        )));
+    }
+
+    Ok(solver.query(name, arguments)?.to_string())
+}
+
+fn help(puzzle: &Puzzle) -> String {
+    let commands = COMMANDS
+        .iter()
+        .copied()
+        .chain(
+            puzzle
+                .queries
+                .iter()
+                .map(|query| (query.name, query.arguments, query.description)),
+        )
+        .map(|(name, arguments, description)| (format!("{name} {arguments}").trim_end().to_string(), description))
+        .collect::<Vec<_>>();
+    let width = commands.iter().map(|(usage, _)| usage.len()).max().unwrap_or_default();
+
+    commands
+        .iter()
+        .map(|(usage, description)| format!("  {usage:width$}  {description}"))
+        .join("\n")
+}
+
+#[cfg(all(test, feature = "day08"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_are_answered_until_the_end_of_the_script() {
+        let puzzle = Puzzle::find(8).unwrap();
+        let solver = Solver::from_reader(8, "30373\n25512\n65332\n33549\n35390\n".as_bytes()).unwrap();
+        let script = "scenic 2 3\n\nfoo 1\nscenic 2\nsolve three\nsolve one\n";
+        let mut transcript = Vec::default();
+
+        run(puzzle, &solver, script.as_bytes(), &mut transcript).unwrap();
+
+        assert_eq!(
+            String::from_utf8(transcript).unwrap(),
+            "day 8 (Treetop Tree House): input parsed, type 'help' to list the commands\n\
+             day 08> 8\n\
+             day 08> \
+             day 08> error: Invalid input: unknown command 'foo', type 'help' to list the commands\n\
+             day 08> error: Invalid input: usage: scenic x y\n\
+             day 08> error: Invalid input: solve: part must be one or two (got 'three')\n\
+             day 08> 21\n\
+             day 08> \n"
+        );
+    }
+
+    #[test]
+    fn quit_stops_reading() {
+        let puzzle = Puzzle::find(8).unwrap();
+        let solver = Solver::from_reader(8, "1\n".as_bytes()).unwrap();
+        let mut transcript = Vec::default();
+
+        run(puzzle, &solver, "quit\nsolve one\n".as_bytes(), &mut transcript).unwrap();
+
+        assert!(String::from_utf8(transcript).unwrap().ends_with("day 08> "));
+    }
+}
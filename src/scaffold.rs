@@ -0,0 +1,646 @@
+//! Generation of the skeleton of a new day.
+//!
+//! The templates below use `%DAY%` for the day number and `%NN%` for the zero-padded one.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Context};
+
+use crate::answers::{Answers, Outcome};
+use crate::solver::{Error, PuzzlePart, Result};
+
+const SOLVER_TEMPLATE: &str = r#"//! Day %DAY%.
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::solver::{Error, Parameters, Puzzle, PuzzlePart, Records, Result, Solve};
+use crate::trace::Tracer;
+
+pub const PUZZLE: Puzzle = Puzzle {
+    day: %DAY%,
+    title: "Day %DAY%",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[],
+    queries: &[],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: None,
+    generator: &Generator,
+};
+
+pub struct Solver {
+    lines: Vec<String>,
+}
+
+impl Solver {
+    pub fn from_reader<R: Read>(reader: BufReader<R>) -> Result<Self> {
+        Self::from_reader_with(reader, &mut Records::default())
+    }
+
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with<R: Read>(reader: BufReader<R>, records: &mut Records) -> Result<Self> {
+        let lines = records.collect::<_, Vec<_>>(reader.lines().enumerate().map(|(i, line)| {
+            let line = line?;
+
+            if line.is_empty() {
+                Err(Error::invalid_input("wrong line: expected a record (got an empty line)").on_line(i + 1, &line))
+            } else {
+                Ok(line)
+            }
+        }))?;
+
+        records.finish()?;
+
+        if lines.is_empty() {
+            return Err(Error::EmptyInput);
+        }
+
+        Ok(Self { lines })
+    }
+}
+
+impl Solve for Solver {
+    fn solve_with(&self, parameters: &Parameters, _tracer: &mut dyn Tracer) -> Result<Answer> {
+        Err(Error::NoSolution(format!(
+            "part {} is not solved yet ({} line(s) parsed)",
+            parameters.puzzle_part(),
+            self.lines.len()
+        )))
+    }
+}
+
+/// Generates `size` lines.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        for _ in 0..size {
+            writeln!(writer, "{}", rng.gen_range(0..100))?;
+        }
+
+        Ok(())
+    }
+}
+"#;
+
+const PEST_SOLVER_TEMPLATE: &str = r#"//! Day %DAY%.
+
+use std::io::{self, Read, Write};
+
+use pest::Parser;
+use pest_derive::Parser;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::answer::Answer;
+use crate::generator::Generate;
+use crate::solver::{Error, Parameters, Puzzle, PuzzlePart, Records, Result, Solve};
+use crate::trace::Tracer;
+
+pub const PUZZLE: Puzzle = Puzzle {
+    day: %DAY%,
+    title: "Day %DAY%",
+    parts: &[PuzzlePart::One, PuzzlePart::Two],
+    parameters: &[],
+    queries: &[],
+    parse: |reader, records| Ok(Solver::from_reader_with(reader, records)?.into()),
+    stream: None,
+    generator: &Generator,
+};
+
+#[derive(Parser)]
+#[grammar = "days/day%NN%/grammar.pest"]
+pub struct Solver {
+    lines: Vec<String>,
+}
+
+impl Solver {
+    pub fn from_reader(reader: impl Read) -> Result<Self> {
+        Self::from_reader_with(reader, &mut Records::default())
+    }
+
+    /// Parses the input, handing its malformed records to `records`.
+    pub fn from_reader_with(reader: impl Read, records: &mut Records) -> Result<Self> {
+        let input = io::read_to_string(reader)?;
+
+        let tokens = Self::parse(Rule::Input, &input)
+            .map_err(|e| {
+                records.grammar_error(&input, e, |line| {
+                    Self::parse(Rule::Line, line)?;
+
+                    Ok(())
+                })
+            })?
+            .next()
+            .unwrap();
+
+        let lines = tokens
+            .into_inner()
+            .filter(|token| token.as_rule() == Rule::Record)
+            .map(|token| token.as_str().to_string())
+            .collect::<Vec<_>>();
+
+        if lines.is_empty() {
+            return Err(Error::EmptyInput);
+        }
+
+        Ok(Self { lines })
+    }
+}
+
+impl Solve for Solver {
+    fn solve_with(&self, parameters: &Parameters, _tracer: &mut dyn Tracer) -> Result<Answer> {
+        Err(Error::NoSolution(format!(
+            "part {} is not solved yet ({} line(s) parsed)",
+            parameters.puzzle_part(),
+            self.lines.len()
+        )))
+    }
+}
+
+/// Generates `size` lines.
+pub struct Generator;
+
+impl Generate for Generator {
+    fn generate(&self, rng: &mut ChaCha8Rng, size: usize, writer: &mut dyn Write) -> Result<()> {
+        for _ in 0..size {
+            writeln!(writer, "{}", rng.gen_range(0..100))?;
+        }
+
+        Ok(())
+    }
+}
+"#;
+
+const GRAMMAR_TEMPLATE: &str = r#"Record = @{ (!NEWLINE ~ ANY)+ }
+Input = { SOI ~ (Record ~ NEWLINE)* ~ EOI }
+Line = { Record ~ NEWLINE ~ EOI }
+"#;
+
+/// Start of the attribute compiling an item of a day registry only when the cargo feature of the day is enabled.
+const FEATURE_PREFIX: &str = "#[cfg(feature = \"day";
+
+/// Fixtures created empty, to be filled with the puzzle example and input.
+const FIXTURES: &[&str] = &["example1", "input"];
+
+/// Manifest of the recorded answers in the `data` directory, where the answers of the new fixtures are pending.
+const ANSWERS_FILE: &str = "answers";
+
+pub struct Scaffold {
+    pub day: u16,
+    /// Parses the input with a pest grammar instead of reading lines.
+    pub pest: bool,
+}
+
+impl Scaffold {
+    /// Creates the module of the day and its fixtures in the crate at `root`, then registers it along with its cargo
+    /// feature, its answers being pending in the manifest.
+    ///
+    /// Returns the created files. Nothing is left behind when failing.
+    pub fn run(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        if !(1..=25).contains(&self.day) {
+            return Err(Error::invalid_input(format!(
+                "day must be between 1 and 25 (got {})",
+                self.day
+            )));
+        }
+
+        let days = root.join("src").join("days");
+        let module = format!("day{:02}", self.day);
+
+        if days.join(&module).exists() || days.join(format!("{module}.rs")).exists() {
+            return Err(eyre!("day {} already exists in '{}'", self.day, days.display()).into());
+        }
+
+        // registries are edited in memory first, so that a failure leaves the crate untouched
+        let directory = days.join(&module);
+        let mut files = Vec::default();
+
+        if self.pest {
+            files.push((directory.join("grammar.pest"), GRAMMAR_TEMPLATE.to_string()));
+            files.push((directory.join("mod.rs"), self.render(PEST_SOLVER_TEMPLATE)));
+        } else {
+            files.push((directory.join("mod.rs"), self.render(SOLVER_TEMPLATE)));
+        }
+
+        for fixture in FIXTURES {
+            let path = root.join("data").join(format!("{fixture}_{module}"));
+
+            if !path.exists() {
+                files.push((path, String::default()));
+            }
+        }
+
+        let registries = [
+            edit(&days.join("mod.rs"), |content| {
+                let content = insert_line(
+                    content,
+                    FEATURE_PREFIX,
+                    &format!("{FEATURE_PREFIX}{:02}\")]\npub mod {module};", self.day),
+                    self.day,
+                )?;
+
+                let content = insert_line(
+                    &content,
+                    &format!("    {FEATURE_PREFIX}"),
+                    &format!("    {FEATURE_PREFIX}{:02}\")]\n    {module}::PUZZLE,", self.day),
+                    self.day,
+                )?;
+                let content = insert_line(
+                    &content,
+                    "    (\"day",
+                    &format!("    (\"{module}\", cfg!(feature = \"{module}\")),"),
+                    self.day,
+                )?;
+
+                insert_line(
+                    &content,
+                    "    feature = \"day",
+                    &format!("    feature = \"{module}\","),
+                    self.day,
+                )
+            })?,
+            edit(&root.join("Cargo.toml"), |content| register_feature(content, self.day))?,
+            edit(&root.join("src").join("solver.rs"), |content| {
+                insert_line(
+                    content,
+                    &format!("    {FEATURE_PREFIX}"),
+                    &format!(
+                        "    {FEATURE_PREFIX}{:02}\")]\n    Day{:02}({module}::Solver),",
+                        self.day, self.day
+                    ),
+                    self.day,
+                )
+            })?,
+        ];
+
+        let answers_path = root.join("data").join(ANSWERS_FILE);
+        let mut answers = Answers::load(&answers_path)?;
+
+        // fixtures without day suffix, e.g. `empty`, are also solved as the new day
+        for fixture in FIXTURES
+            .iter()
+            .map(|fixture| format!("{fixture}_{module}"))
+            .chain(shared_fixtures(root))
+        {
+            for puzzle_part in [PuzzlePart::One, PuzzlePart::Two] {
+                answers.insert(&fixture, self.day, puzzle_part, Outcome::Pending);
+            }
+        }
+
+        let created = files.iter().map(|(path, _)| path.clone()).collect();
+        let written = fs::create_dir_all(&directory)
+            .wrap_err_with(|| format!("Cannot create '{}'", directory.display()))
+            .map_err(Error::from)
+            .and_then(|_| {
+                write_all(
+                    files
+                        .into_iter()
+                        .chain(registries)
+                        .chain([(answers_path, answers.to_string())]),
+                )
+            });
+
+        if let Err(e) = written {
+            // best effort: the error of the scaffold matters more than the one of its cleanup
+            let _ = fs::remove_dir_all(&directory);
+
+            return Err(e);
+        }
+
+        Ok(created)
+    }
+
+    fn render(&self, template: &str) -> String {
+        template
+            .replace("%DAY%", &self.day.to_string())
+            .replace("%NN%", &format!("{:02}", self.day))
+    }
+}
+
+/// Writes every file or none of them: when failing, the files written so far get back their previous content, or
+/// are removed if they did not exist.
+fn write_all(files: impl IntoIterator<Item = (PathBuf, String)>) -> Result<()> {
+    let mut previous_contents = Vec::default();
+    let written = files.into_iter().try_for_each(|(path, content)| {
+        let previous_content = if path.exists() {
+            Some(fs::read_to_string(&path).wrap_err_with(|| format!("Cannot read '{}'", path.display()))?)
+        } else {
+            None
+        };
+
+        previous_contents.push((path.clone(), previous_content));
+        fs::write(&path, content).wrap_err_with(|| format!("Cannot write '{}'", path.display()))?;
+
+        Ok(())
+    });
+
+    if written.is_err() {
+        // best effort: the error of the write matters more than the ones of its rollback
+        for (path, previous_content) in previous_contents.into_iter().rev() {
+            let _ = match previous_content {
+                Some(content) => fs::write(&path, content),
+                None => fs::remove_file(&path),
+            };
+        }
+    }
+
+    written
+}
+
+/// New content of the file at `path`, edited by `f` without being written.
+fn edit(path: &Path, f: impl FnOnce(&str) -> Result<String>) -> Result<(PathBuf, String)> {
+    let content = fs::read_to_string(path).wrap_err_with(|| format!("Cannot read '{}'", path.display()))?;
+    let content = f(&content).map_err(|e| e.in_file(path.display().to_string()))?;
+
+    Ok((path.to_path_buf(), content))
+}
+
+/// Names of the fixtures of the crate at `root` without day suffix, shared by every day.
+fn shared_fixtures(root: &Path) -> Vec<String> {
+    fs::read_dir(root.join("data"))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name != ANSWERS_FILE && !name.contains("_day"))
+        .collect()
+}
+
+/// Declares the cargo feature of `day` in a manifest and adds it to the features enabled by `all-days`, keeping both
+/// sorted, unless already done.
+fn register_feature(content: &str, day: u16) -> Result<String> {
+    let feature = format!("day{day:02}");
+    let declaration = format!("{feature} = []");
+    let content = if content.lines().any(|line| line == declaration) {
+        content.to_string()
+    } else {
+        insert_line(content, "day", &declaration, day)?
+    };
+
+    let mut registered = false;
+    let lines = content
+        .lines()
+        .map(|line| {
+            let Some(features) = line
+                .strip_prefix("all-days = [")
+                .and_then(|rest| rest.strip_suffix(']'))
+            else {
+                return line.to_string();
+            };
+            let quoted = format!("\"{feature}\"");
+            let mut features = features.split(", ").filter(|name| !name.is_empty()).collect::<Vec<_>>();
+
+            if !features.contains(&quoted.as_str()) {
+                features.push(&quoted);
+                features.sort_unstable();
+            }
+
+            registered = true;
+
+            format!("all-days = [{}]", features.join(", "))
+        })
+        .collect::<Vec<_>>();
+
+    if !registered {
+        return Err(Error::invalid_input(format!(
+            "no 'all-days' feature to register '{feature}'"
+        )));
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Inserts `line` among the lines of a day registry, being the consecutive lines starting with `prefix` followed
+/// by a zero-padded day, so that the registry stays sorted by day.
+///
+/// An entry of the registry spans as many lines as `line`, of which only the first one starts with `prefix`.
+fn insert_line(content: &str, prefix: &str, line: &str, day: u16) -> Result<String> {
+    let registry_day = |line: &str| {
+        line.strip_prefix(prefix)
+            .and_then(|rest| rest.get(..2))
+            .and_then(|day| day.parse::<u16>().ok())
+    };
+
+    let mut lines = content.lines().collect::<Vec<_>>();
+    let last = lines
+        .iter()
+        .rposition(|line| registry_day(line).is_some())
+        .ok_or_else(|| Error::invalid_input(format!("no line starting with '{prefix}' to register day {day}")))?;
+    let position = lines
+        .iter()
+        .position(|line| registry_day(line).is_some_and(|registered| registered > day))
+        .unwrap_or(last + line.lines().count());
+
+    lines.insert(position, line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_TOML: &str = r#"[features]
+default = ["all-days"]
+all-days = ["day01", "day03"]
+day01 = []
+day03 = []
+"#;
+
+    const DAYS: &str = r#"#[cfg(feature = "day01")]
+pub mod day01;
+#[cfg(feature = "day03")]
+pub mod day03;
+
+pub(crate) const PUZZLES: &[Puzzle] = &[
+    #[cfg(feature = "day01")]
+    day01::PUZZLE,
+    #[cfg(feature = "day03")]
+    day03::PUZZLE,
+];
+
+const FEATURES: &[(&str, bool)] = &[
+    ("day01", cfg!(feature = "day01")),
+    ("day03", cfg!(feature = "day03")),
+];
+
+#[cfg(not(any(
+    feature = "day01",
+    feature = "day03",
+)))]
+compile_error!("no day");
+"#;
+
+    const SOLVER: &str = r#"pub enum Solver {
+    #[cfg(feature = "day01")]
+    Day01(day01::Solver),
+    #[cfg(feature = "day03")]
+    Day03(day03::Solver),
+}
+"#;
+
+    const ANSWERS: &str = "[empty 1 one]\n!InvalidInput\n";
+
+    /// Crate implementing days 1 and 3 in a temporary directory, removed when dropped.
+    struct Crate(PathBuf);
+
+    impl Crate {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("scaffold-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+
+            for (path, content) in [
+                ("Cargo.toml", CARGO_TOML),
+                ("src/solver.rs", SOLVER),
+                ("src/days/mod.rs", DAYS),
+                ("src/days/day01.rs", ""),
+                ("src/days/day03.rs", ""),
+                ("data/answers", ANSWERS),
+                ("data/empty", ""),
+            ] {
+                let path = root.join(path);
+
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, content).unwrap();
+            }
+
+            Self(root)
+        }
+
+        fn read(&self, path: &str) -> String {
+            fs::read_to_string(self.0.join(path)).unwrap()
+        }
+    }
+
+    impl Drop for Crate {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn registries_stay_sorted_by_day() {
+        let root = Crate::new("sorted");
+
+        for day in [2, 4] {
+            Scaffold { day, pest: false }.run(&root.0).unwrap();
+        }
+
+        assert_eq!(
+            root.read("Cargo.toml"),
+            r#"[features]
+default = ["all-days"]
+all-days = ["day01", "day02", "day03", "day04"]
+day01 = []
+day02 = []
+day03 = []
+day04 = []
+"#
+        );
+        assert_eq!(
+            root.read("src/days/mod.rs"),
+            r#"#[cfg(feature = "day01")]
+pub mod day01;
+#[cfg(feature = "day02")]
+pub mod day02;
+#[cfg(feature = "day03")]
+pub mod day03;
+#[cfg(feature = "day04")]
+pub mod day04;
+
+pub(crate) const PUZZLES: &[Puzzle] = &[
+    #[cfg(feature = "day01")]
+    day01::PUZZLE,
+    #[cfg(feature = "day02")]
+    day02::PUZZLE,
+    #[cfg(feature = "day03")]
+    day03::PUZZLE,
+    #[cfg(feature = "day04")]
+    day04::PUZZLE,
+];
+
+const FEATURES: &[(&str, bool)] = &[
+    ("day01", cfg!(feature = "day01")),
+    ("day02", cfg!(feature = "day02")),
+    ("day03", cfg!(feature = "day03")),
+    ("day04", cfg!(feature = "day04")),
+];
+
+#[cfg(not(any(
+    feature = "day01",
+    feature = "day02",
+    feature = "day03",
+    feature = "day04",
+)))]
+compile_error!("no day");
+"#
+        );
+        assert_eq!(
+            root.read("src/solver.rs"),
+            r#"pub enum Solver {
+    #[cfg(feature = "day01")]
+    Day01(day01::Solver),
+    #[cfg(feature = "day02")]
+    Day02(day02::Solver),
+    #[cfg(feature = "day03")]
+    Day03(day03::Solver),
+    #[cfg(feature = "day04")]
+    Day04(day04::Solver),
+}
+"#
+        );
+
+        let answers = root.read("data/answers").parse::<Answers>().unwrap();
+
+        for fixture in ["example1_day02", "input_day02", "empty"] {
+            assert_eq!(
+                answers.get(fixture, 2, PuzzlePart::Two),
+                Some(&Outcome::Pending),
+                "{fixture}"
+            );
+        }
+
+        assert_eq!(
+            answers.get("empty", 1, PuzzlePart::One),
+            Some(&Outcome::Error("InvalidInput".to_string()))
+        );
+    }
+
+    #[test]
+    fn feature_registration_is_idempotent() {
+        let registered = register_feature(CARGO_TOML, 2).unwrap();
+
+        assert_eq!(register_feature(&registered, 2).unwrap(), registered);
+        assert_eq!(register_feature(CARGO_TOML, 3).unwrap(), CARGO_TOML);
+    }
+
+    #[test]
+    fn existing_day_is_rejected() {
+        let root = Crate::new("existing");
+
+        assert!(Scaffold { day: 3, pest: true }.run(&root.0).is_err());
+        assert_eq!(root.read("src/days/mod.rs"), DAYS);
+        assert_eq!(root.read("data/answers"), ANSWERS);
+    }
+
+    #[test]
+    fn failed_write_restores_files() {
+        let root = Crate::new("failed");
+        let new_file = root.0.join("data/example1_day02");
+
+        let written = write_all([
+            (root.0.join("Cargo.toml"), String::default()),
+            (new_file.clone(), "1\n".to_string()),
+            (root.0.join("missing/answers"), String::default()),
+        ]);
+
+        assert!(written.is_err());
+        assert_eq!(root.read("Cargo.toml"), CARGO_TOML);
+        assert!(!new_file.exists());
+    }
+}
@@ -1,16 +1,26 @@
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::{self, BufReader};
-use std::num;
-use std::path::Path;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fmt, num};
 
 use clap::ValueEnum;
 use enum_dispatch::enum_dispatch;
 use eyre::Context;
+use itertools::Itertools;
+use pest::RuleType;
+use strum::{Display, EnumString, IntoStaticStr};
 use thiserror::Error;
 
+use crate::answer::Answer;
 use crate::days::*;
+use crate::diagnostic::Diagnostic;
+use crate::generator::Generate;
+use crate::normalize::{Normalization, Normalizer};
+use crate::trace::{NoTracer, Tracer};
 
-#[derive(Debug, Error)]
+#[derive(Debug, Error, IntoStaticStr)]
 pub enum Error {
     #[error(transparent)]
     EyreReport(#[from] eyre::ErrReport),
@@ -20,19 +30,168 @@ pub enum Error {
     ParseIntError(#[from] num::ParseIntError),
     #[error(transparent)]
     ParseBigIntError(#[from] num_bigint::ParseBigIntError),
-    #[error("Unimplemented day {0}")]
+    #[error("Unimplemented day {0} (available days: {})", Solver::PUZZLES.iter().map(|puzzle| puzzle.day).join(", "))]
     UnimplementedDay(u16),
-    #[error("Invalid input: {0}")]
-    InvalidInput(String),
+    #[error("Day {0} not compiled in (enable the cargo feature 'day{0:02}')")]
+    DayNotCompiled(u16),
+    #[error("{0}")]
+    InvalidInput(Box<Diagnostic>),
     #[error("No solution found: {0}")]
     NoSolution(String),
     #[error("Empty input")]
     EmptyInput,
+    #[error("{}\n\n{} malformed record(s)", .0.iter().join("\n\n"), .0.len())]
+    MalformedRecords(Vec<Error>),
+}
+
+impl Error {
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Diagnostic::new(message).into()
+    }
+
+    /// Locates an invalid input of `fragment` in `source`, see [`Diagnostic::within`].
+    pub fn within(self, source: &str, fragment: &str) -> Self {
+        self.map_diagnostic(&|diagnostic| diagnostic.within(source, fragment))
+    }
+
+    /// Locates an invalid input at the `line`-th line of the input, whose content is `source`.
+    pub fn on_line(self, line: usize, source: &str) -> Self {
+        self.map_diagnostic(&|diagnostic| diagnostic.within(source, source).at_line(line))
+    }
+
+    pub fn in_file(self, file: impl Into<String>) -> Self {
+        let file = file.into();
+
+        self.map_diagnostic(&|diagnostic| diagnostic.in_file(file.clone()))
+    }
+
+    fn map_diagnostic(self, f: &dyn Fn(Diagnostic) -> Diagnostic) -> Self {
+        match self {
+            Self::InvalidInput(diagnostic) => Self::InvalidInput(Box::new(f(*diagnostic))),
+            Self::MalformedRecords(errors) => {
+                Self::MalformedRecords(errors.into_iter().map(|e| e.map_diagnostic(f)).collect())
+            }
+            e => e,
+        }
+    }
+
+    /// Name of the error variant, looking through the context of wrapped errors.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::EyreReport(report) => report.downcast_ref::<Self>().map_or_else(|| self.into(), Self::kind),
+            _ => self.into(),
+        }
+    }
+}
+
+impl From<Diagnostic> for Error {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Self::InvalidInput(Box::new(diagnostic))
+    }
+}
+
+impl<R: RuleType> From<pest::error::Error<R>> for Error {
+    fn from(e: pest::error::Error<R>) -> Self {
+        Diagnostic::from(e).into()
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+/// Handling of the malformed records of an input: parsing stops at the first one, unless collecting them all.
+///
+/// While collecting, malformed records are skipped so that the following ones are still checked, then
+/// [`Records::finish`] reports them all at once in an [`Error::MalformedRecords`]. Checks of the whole input are
+/// meant to run after it, since skipped records would make them fail for no reason.
+#[derive(Debug, Default)]
+pub struct Records {
+    collecting: bool,
+    errors: Vec<Error>,
+}
+
+impl Records {
+    /// Collects every malformed record instead of stopping at the first one.
+    pub fn collecting() -> Self {
+        Self {
+            collecting: true,
+            errors: Vec::default(),
+        }
+    }
+
+    pub fn is_collecting(&self) -> bool {
+        self.collecting
+    }
+
+    /// Reports a malformed record, returning `error` unless it is an invalid input being collected.
+    pub fn report(&mut self, error: Error) -> Result<()> {
+        match error {
+            Error::InvalidInput(_) if self.collecting => {
+                self.errors.push(error);
+
+                Ok(())
+            }
+            e => Err(e),
+        }
+    }
+
+    /// Value of a record, `None` when it is malformed and collected.
+    pub fn check<T>(&mut self, record: Result<T>) -> Result<Option<T>> {
+        match record {
+            Ok(value) => Ok(Some(value)),
+            Err(e) => self.report(e).map(|_| None),
+        }
+    }
+
+    /// Values of the well-formed `records`.
+    pub fn collect<T, C: FromIterator<T>>(&mut self, records: impl IntoIterator<Item = Result<T>>) -> Result<C> {
+        records
+            .into_iter()
+            .filter_map(|record| self.check(record).transpose())
+            .collect()
+    }
+
+    /// Reports the error of a grammar parsing the whole `input` at once, or the errors of every line it rejects
+    /// when collecting.
+    ///
+    /// Lines are given to `check_line` with their line feed, if any, and are numbered from 1. The error of the
+    /// whole input is kept when no line is rejected, e.g. for an empty input.
+    pub fn grammar_error<R: RuleType>(
+        &mut self,
+        input: &str,
+        error: pest::error::Error<R>,
+        check_line: impl Fn(&str) -> Result<()>,
+    ) -> Error {
+        if !self.collecting {
+            return error.into();
+        }
+
+        let errors = self.errors.len();
+
+        for (i, line) in input.split_inclusive('\n').enumerate() {
+            if let Err(e) = check_line(line) {
+                self.errors.push(e.on_line(i + 1, line.trim_end_matches(['\r', '\n'])));
+            }
+        }
+
+        if self.errors.len() == errors {
+            self.errors.push(error.into());
+        }
+
+        self.finish().unwrap_err()
+    }
+
+    /// Reports the malformed records collected so far, if any.
+    pub fn finish(&mut self) -> Result<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::MalformedRecords(std::mem::take(&mut self.errors)))
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
 pub enum PuzzlePart {
     One,
     Two,
@@ -40,42 +199,361 @@ pub enum PuzzlePart {
 
 #[enum_dispatch]
 pub enum Solver {
+    #[cfg(feature = "day01")]
     Day01(day01::Solver),
+    #[cfg(feature = "day02")]
     Day02(day02::Solver),
+    #[cfg(feature = "day03")]
     Day03(day03::Solver),
+    #[cfg(feature = "day04")]
     Day04(day04::Solver),
+    #[cfg(feature = "day05")]
     Day05(day05::Solver),
+    #[cfg(feature = "day06")]
     Day06(day06::Solver),
+    #[cfg(feature = "day07")]
     Day07(day07::Solver),
+    #[cfg(feature = "day08")]
     Day08(day08::Solver),
+    #[cfg(feature = "day09")]
     Day09(day09::Solver),
+    #[cfg(feature = "day10")]
     Day10(day10::Solver),
 }
 
 #[enum_dispatch(Solver)]
 pub trait Solve {
-    fn solve(&self, puzzle_part: PuzzlePart) -> Result<String>;
+    /// Solves with the parameter preset of `puzzle_part`.
+    fn solve(&self, puzzle_part: PuzzlePart) -> Result<Answer> {
+        self.solve_with(&Parameters::preset(puzzle_part), &mut NoTracer)
+    }
+
+    /// Solves with `parameters` while reporting the steps of the solver to `tracer`, days without events
+    /// ignoring it.
+    fn solve_with(&self, parameters: &Parameters, tracer: &mut dyn Tracer) -> Result<Answer>;
+
+    /// Answers the query named `name`, one of the queries of the puzzle.
+    fn query(&self, name: &str, _arguments: &[&str]) -> Result<Answer> {
+        Err(Error::invalid_input(format!("unknown query '{name}'")))
+    }
 }
 
 impl Solver {
+    pub const PUZZLES: &'static [Puzzle] = PUZZLES;
+
+    /// Parses the input file of `day` as is, see [`Input`] for a normalized input.
     pub fn from_file(path: &Path, day: u16) -> Result<Self> {
+        // reports unimplemented days before any missing input
+        Puzzle::find(day)?;
+
         let file = File::open(path).wrap_err_with(|| format!("Cannot open file '{}'", path.display()))?;
-        let reader = BufReader::new(file);
-
-        let solver = match day {
-            1 => day01::Solver::from_reader(reader)?.into(),
-            2 => day02::Solver::from_reader(reader)?.into(),
-            3 => day03::Solver::from_reader(reader)?.into(),
-            4 => day04::Solver::from_reader(reader)?.into(),
-            5 => day05::Solver::from_reader(reader)?.into(),
-            6 => day06::Solver::from_reader(reader)?.into(),
-            7 => day07::Solver::from_reader(reader)?.into(),
-            8 => day08::Solver::from_reader(reader)?.into(),
-            9 => day09::Solver::from_reader(reader)?.into(),
-            10 => day10::Solver::from_reader(reader)?.into(),
-            _ => return Err(Error::UnimplementedDay(day)),
+
+        Self::from_reader(day, file).map_err(|e| e.in_file(path.display().to_string()))
+    }
+
+    pub fn from_reader(day: u16, reader: impl Read) -> Result<Self> {
+        Self::from_reader_with(day, reader, &mut Records::default())
+    }
+
+    /// Parses the input of `day`, handing its malformed records to `records`.
+    pub fn from_reader_with(day: u16, mut reader: impl Read, records: &mut Records) -> Result<Self> {
+        let puzzle = Puzzle::find(day)?;
+
+        (puzzle.parse)(BufReader::new(&mut reader), records)
+    }
+}
+
+/// Solver of a puzzle part reading its input as it goes, see [`Puzzle::stream`].
+pub type StreamingSolve = fn(BufReader<&mut dyn Read>, &Parameters) -> Result<Answer>;
+
+/// Metadata and constructor of a day, registered in `days::PUZZLES`.
+pub struct Puzzle {
+    pub day: u16,
+    pub title: &'static str,
+    pub parts: &'static [PuzzlePart],
+    pub parameters: &'static [Parameter],
+    /// Questions about a parsed input answered by [`Solve::query`], asked from the REPL.
+    pub queries: &'static [Query],
+    /// Parses an input, handing its malformed records to the given [`Records`].
+    pub parse: fn(BufReader<&mut dyn Read>, &mut Records) -> Result<Solver>,
+    /// Solves a puzzle part while reading the input, for days whose records can be processed one at a time.
+    pub stream: Option<StreamingSolve>,
+    pub generator: &'static dyn Generate,
+}
+
+impl Puzzle {
+    pub fn find(day: u16) -> Result<&'static Self> {
+        Solver::PUZZLES.iter().find(|puzzle| puzzle.day == day).ok_or_else(|| {
+            if crate::days::is_compiled_out(day) {
+                Error::DayNotCompiled(day)
+            } else {
+                Error::UnimplementedDay(day)
+            }
+        })
+    }
+}
+
+/// Constant of a puzzle, with the default value used by each puzzle part.
+pub struct Parameter {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub defaults: [&'static str; 2],
+    pub kind: ParameterKind,
+}
+
+impl Parameter {
+    pub fn default_value(&self, puzzle_part: PuzzlePart) -> &'static str {
+        match puzzle_part {
+            PuzzlePart::One => self.defaults[0],
+            PuzzlePart::Two => self.defaults[1],
+        }
+    }
+
+    /// Checks that `value` is accepted by the parameter.
+    pub fn check(&self, value: &str) -> Result<()> {
+        let accepted = match self.kind {
+            ParameterKind::Unsigned { min, max } => value.parse::<u64>().is_ok_and(|n| (min..=max).contains(&n)),
+            ParameterKind::Choice(choices) => choices.contains(&value),
         };
 
-        Ok(solver)
+        accepted.then_some(()).ok_or_else(|| {
+            Error::invalid_input(format!(
+                "parameter '{}' must be {} (got '{value}')",
+                self.name, self.kind
+            ))
+        })
+    }
+}
+
+/// Values accepted by a [`Parameter`].
+#[derive(Debug, Copy, Clone)]
+pub enum ParameterKind {
+    /// Unsigned integer from `min` to `max`, both included.
+    Unsigned { min: u64, max: u64 },
+    /// One of the listed values.
+    Choice(&'static [&'static str]),
+}
+
+impl fmt::Display for ParameterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsigned { min, max: u64::MAX } => write!(f, "an unsigned integer from {min}"),
+            Self::Unsigned { min, max } => write!(f, "an unsigned integer from {min} to {max}"),
+            Self::Choice(choices) => write!(f, "one of {}", choices.join(", ")),
+        }
+    }
+}
+
+/// Parameters of a solver: the preset of a puzzle part, whose values may be overridden.
+#[derive(Debug, Clone)]
+pub struct Parameters {
+    puzzle_part: PuzzlePart,
+    overrides: BTreeMap<&'static str, String>,
+}
+
+impl Parameters {
+    /// Default values of the parameters for `puzzle_part`.
+    pub fn preset(puzzle_part: PuzzlePart) -> Self {
+        Self {
+            puzzle_part,
+            overrides: BTreeMap::default(),
+        }
+    }
+
+    /// Overrides parameters of `puzzle` from `name=value` assignments, checking both names and values.
+    pub fn with_assignments(mut self, puzzle: &Puzzle, assignments: &[String]) -> Result<Self> {
+        if let (true, Some(assignment)) = (puzzle.parameters.is_empty(), assignments.first()) {
+            return Err(Error::invalid_input(format!(
+                "day {} has no parameters (got '{assignment}')",
+                puzzle.day
+            )));
+        }
+
+        for assignment in assignments {
+            let (name, value) = assignment.split_once('=').ok_or_else(|| {
+                Error::invalid_input(format!("wrong parameter: expected 'name=value' (got '{assignment}')"))
+            })?;
+            let parameter = puzzle
+                .parameters
+                .iter()
+                .find(|parameter| parameter.name == name)
+                .ok_or_else(|| {
+                    Error::invalid_input(format!(
+                        "unknown parameter '{name}' of day {} (parameters: {})",
+                        puzzle.day,
+                        puzzle.parameters.iter().map(|parameter| parameter.name).join(", ")
+                    ))
+                })?;
+
+            parameter.check(value)?;
+            self.overrides.insert(parameter.name, value.to_string());
+        }
+
+        Ok(self)
+    }
+
+    pub fn puzzle_part(&self) -> PuzzlePart {
+        self.puzzle_part
+    }
+
+    /// Value of `parameter`, either overridden or taken from the preset.
+    pub fn get<T: FromStr>(&self, parameter: &Parameter) -> Result<T> {
+        let value = self
+            .overrides
+            .get(parameter.name)
+            .map_or(parameter.default_value(self.puzzle_part), String::as_str);
+
+        value.parse().map_err(|_| {
+            Error::invalid_input(format!(
+                "parameter '{}' must be {} (got '{value}')",
+                parameter.name, parameter.kind
+            ))
+        })
+    }
+}
+
+/// Question about a parsed input, named `name` and taking the whitespace separated `arguments`.
+pub struct Query {
+    pub name: &'static str,
+    pub arguments: &'static str,
+    pub description: &'static str,
+}
+
+impl Query {
+    pub fn arity(&self) -> usize {
+        self.arguments.split_whitespace().count()
+    }
+
+    /// Parses the `i`-th argument, whose kind (e.g. "an unsigned integer") is reported on errors.
+    pub fn argument<T: FromStr>(&self, arguments: &[&str], i: usize, kind: &str) -> Result<T> {
+        let name = self.arguments.split_whitespace().nth(i).unwrap_or("argument");
+        let value = arguments
+            .get(i)
+            .ok_or_else(|| Error::invalid_input(format!("{}: missing {name}", self.name)))?;
+
+        value
+            .parse()
+            .map_err(|_| Error::invalid_input(format!("{}: {name} must be {kind} (got '{value}')", self.name)))
+    }
+}
+
+/// Puzzle input which can be parsed as many times as needed, normalized each time it is read.
+#[derive(Debug, Clone)]
+pub struct Input {
+    source: Source,
+    normalization: Normalization,
+}
+
+#[derive(Debug, Clone)]
+enum Source {
+    File(PathBuf),
+    Memory(Vec<u8>),
+}
+
+impl Input {
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            source: Source::File(path.into()),
+            normalization: Normalization::default(),
+        }
+    }
+
+    pub fn memory(buffer: Vec<u8>) -> Self {
+        Self {
+            source: Source::Memory(buffer),
+            normalization: Normalization::default(),
+        }
+    }
+
+    pub fn from_stdin() -> Result<Self> {
+        let mut buffer = Vec::default();
+
+        io::stdin().lock().read_to_end(&mut buffer)?;
+
+        Ok(Self::memory(buffer))
+    }
+
+    pub fn with_normalization(self, normalization: Normalization) -> Self {
+        Self { normalization, ..self }
+    }
+
+    /// Name of the input file, `None` for inputs read from memory.
+    pub fn file_name(&self) -> Option<&str> {
+        match &self.source {
+            Source::File(path) => path.file_name().and_then(|name| name.to_str()),
+            Source::Memory(_) => None,
+        }
+    }
+
+    pub fn parse(&self, day: u16) -> Result<Solver> {
+        self.parse_with(day, &mut Records::default())
+    }
+
+    /// Parses the whole input, returning every malformed record found, none for a valid input.
+    ///
+    /// Errors other than invalid inputs, e.g. a missing file, are returned as is.
+    pub fn validate(&self, day: u16) -> Result<Vec<Error>> {
+        match self.parse_with(day, &mut Records::collecting()) {
+            Ok(_) => Ok(Vec::default()),
+            Err(Error::MalformedRecords(errors)) => Ok(errors),
+            Err(e @ (Error::InvalidInput(_) | Error::EmptyInput)) => Ok(vec![e]),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn parse_with(&self, day: u16, records: &mut Records) -> Result<Solver> {
+        // reports unimplemented days before any missing input
+        Puzzle::find(day)?;
+
+        Solver::from_reader_with(day, self.open()?, records).map_err(|e| self.locate(e))
+    }
+
+    /// Solves with `parameters` while reading the input, without keeping it in memory, see [`Puzzle::stream`].
+    pub fn solve_streaming(&self, day: u16, parameters: &Parameters) -> Result<Answer> {
+        let puzzle = Puzzle::find(day)?;
+        let stream = puzzle.stream.ok_or_else(|| {
+            eyre::eyre!(
+                "day {day} cannot be solved while streaming its input (streaming days: {})",
+                Solver::PUZZLES
+                    .iter()
+                    .filter(|puzzle| puzzle.stream.is_some())
+                    .map(|puzzle| puzzle.day)
+                    .join(", ")
+            )
+        })?;
+
+        stream(BufReader::new(&mut *self.open()?), parameters).map_err(|e| self.locate(e))
+    }
+
+    /// Reader of the input, normalized as requested.
+    fn open(&self) -> Result<Box<dyn Read + '_>> {
+        let reader: Box<dyn Read> = match &self.source {
+            Source::File(path) => {
+                Box::new(File::open(path).wrap_err_with(|| format!("Cannot open file '{}'", path.display()))?)
+            }
+            Source::Memory(buffer) => Box::new(buffer.as_slice()),
+        };
+
+        Ok(match self.normalization {
+            Normalization::Tolerant => Box::new(Normalizer::new(BufReader::new(reader))),
+            Normalization::Strict => reader,
+        })
+    }
+
+    /// Locates the invalid inputs of `e` in the input file, if any.
+    fn locate(&self, e: Error) -> Error {
+        match &self.source {
+            Source::File(path) => e.in_file(path.display().to_string()),
+            Source::Memory(_) => e,
+        }
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Memory(_) => write!(f, "-"),
+        }
     }
 }
@@ -0,0 +1,51 @@
+//! Helpers of the streaming mode, solving a puzzle while reading its input.
+
+use std::io::BufRead;
+
+use crate::solver::Result;
+
+/// Reads the lines of an input one at a time into a reused buffer, so that memory is bounded by the longest line.
+pub struct LineReader<R: BufRead> {
+    reader: R,
+    buffer: String,
+    number: usize,
+}
+
+/// Line read by a [`LineReader`].
+pub struct Line<'a> {
+    /// Number of the line, from 1.
+    pub number: usize,
+    /// Content of the line, without its line ending.
+    pub content: &'a str,
+    /// Whether the line ends with a newline, which may be missing on the last line.
+    pub terminated: bool,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::default(),
+            number: 0,
+        }
+    }
+
+    pub fn next_line(&mut self) -> Result<Option<Line<'_>>> {
+        self.buffer.clear();
+
+        if self.reader.read_line(&mut self.buffer)? == 0 {
+            return Ok(None);
+        }
+
+        self.number += 1;
+
+        let terminated = self.buffer.ends_with('\n');
+        let content = self.buffer.trim_end_matches('\n');
+
+        Ok(Some(Line {
+            number: self.number,
+            content: content.strip_suffix('\r').unwrap_or(content),
+            terminated,
+        }))
+    }
+}
@@ -0,0 +1,96 @@
+//! Observation of the steps run by the solvers, to understand how an answer was found.
+
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::number::Integer;
+use crate::solver::{PuzzlePart, Result};
+
+/// Step of a solver.
+///
+/// Integers are serialized as strings of digits, as big integers in [`crate::answer::Answer`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// Day 05: a crane instruction was applied, `stacks` listing the crates of every stack from bottom to top.
+    Instruction { instruction: String, stacks: Vec<String> },
+    /// Day 07: a shell command was executed, leaving the shell in `working_directory`.
+    Command { command: String, working_directory: String },
+    /// Day 09: the head moved by one step, `knots` being the positions of every knot from the head.
+    Step { knots: Vec<(Integer, Integer)> },
+    /// Day 10: value of the register X during a clock cycle.
+    Cycle { cycle: usize, x: Integer },
+}
+
+/// Observer of the events of a solver, see [`crate::solver::Solve::solve_with`].
+pub trait Tracer {
+    /// Called before solving `puzzle_part` of the puzzle of `day`.
+    fn begin(&mut self, _day: u16, _puzzle_part: PuzzlePart) {}
+
+    /// Whether events are observed, solvers not building the events otherwise.
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    fn trace(&mut self, event: Event) -> Result<()>;
+}
+
+/// Tracer ignoring every event.
+pub struct NoTracer;
+
+impl Tracer for NoTracer {
+    fn enabled(&self) -> bool {
+        false
+    }
+
+    fn trace(&mut self, _event: Event) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes events as line-delimited JSON records, tagged with the day and the puzzle part being solved.
+pub struct JsonLinesTracer<W: Write> {
+    writer: W,
+    solving: Option<(u16, PuzzlePart)>,
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    day: Option<u16>,
+    part: Option<String>,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+impl<W: Write> JsonLinesTracer<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, solving: None }
+    }
+
+    /// Flushes the records, then returns the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Tracer for JsonLinesTracer<W> {
+    fn begin(&mut self, day: u16, puzzle_part: PuzzlePart) {
+        self.solving = Some((day, puzzle_part));
+    }
+
+    fn trace(&mut self, event: Event) -> Result<()> {
+        let record = Record {
+            day: self.solving.map(|(day, _)| day),
+            part: self.solving.map(|(_, puzzle_part)| puzzle_part.to_string()),
+            event: &event,
+        };
+
+        serde_json::to_writer(&mut self.writer, &record).map_err(std::io::Error::from)?;
+        writeln!(self.writer)?;
+
+        Ok(())
+    }
+}
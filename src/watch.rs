@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use std::{fs, thread};
+
+/// Polls a file until its modification time or its size changes.
+///
+/// Only relies on file metadata, so that no filesystem notification service is needed.
+pub struct Watcher {
+    path: PathBuf,
+    interval: Duration,
+    fingerprint: Option<(SystemTime, u64)>,
+    settling: bool,
+}
+
+impl Watcher {
+    pub fn new(path: impl Into<PathBuf>, interval: Duration) -> Self {
+        let path = path.into();
+        let fingerprint = fingerprint(&path);
+
+        Self {
+            path,
+            interval,
+            fingerprint,
+            settling: false,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Blocks until the file changes, is created or is removed.
+    pub fn wait_for_change(&mut self) {
+        loop {
+            thread::sleep(self.interval);
+
+            if self.observe(fingerprint(&self.path)) {
+                return;
+            }
+        }
+    }
+
+    /// Records a fingerprint of the file taken one interval after the previous one, and tells
+    /// whether a change has settled.
+    ///
+    /// A change is only reported once the fingerprint stayed the same for a whole interval,
+    /// editors may save a file in several steps.
+    fn observe(&mut self, fingerprint: Option<(SystemTime, u64)>) -> bool {
+        let unchanged = fingerprint == self.fingerprint;
+
+        self.fingerprint = fingerprint;
+
+        if self.settling && unchanged {
+            self.settling = false;
+
+            return true;
+        }
+
+        self.settling |= !unchanged;
+
+        false
+    }
+}
+
+fn fingerprint(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: u64, len: u64) -> Option<(SystemTime, u64)> {
+        Some((SystemTime::UNIX_EPOCH + Duration::from_secs(seconds), len))
+    }
+
+    fn watcher(fingerprint: Option<(SystemTime, u64)>) -> Watcher {
+        Watcher {
+            path: PathBuf::from("input"),
+            interval: Duration::from_millis(100),
+            fingerprint,
+            settling: false,
+        }
+    }
+
+    #[test]
+    fn unchanged_file_is_not_reported() {
+        let mut watcher = watcher(at(1, 10));
+
+        assert!((0..5).all(|_| !watcher.observe(at(1, 10))));
+    }
+
+    #[test]
+    fn change_is_reported_once_settled() {
+        let mut watcher = watcher(at(1, 10));
+
+        assert!(!watcher.observe(at(2, 10)));
+        assert!(watcher.observe(at(2, 10)));
+        assert!(!watcher.observe(at(2, 10)));
+    }
+
+    #[test]
+    fn change_in_several_steps_is_reported_once() {
+        let mut watcher = watcher(at(1, 10));
+
+        assert!(!watcher.observe(at(2, 0)));
+        assert!(!watcher.observe(at(2, 5)));
+        assert!(!watcher.observe(at(3, 20)));
+        assert!(watcher.observe(at(3, 20)));
+    }
+
+    #[test]
+    fn creation_and_removal_are_changes() {
+        let mut watcher = watcher(None);
+
+        assert!(!watcher.observe(at(1, 10)));
+        assert!(watcher.observe(at(1, 10)));
+
+        assert!(!watcher.observe(None));
+        assert!(watcher.observe(None));
+    }
+}
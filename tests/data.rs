@@ -0,0 +1,94 @@
+//! Regression tests driven by the fixtures of the `data/` directory.
+//!
+//! Every fixture is read as is, see `tests/normalization.rs` for normalized
+//! inputs, solved for both puzzle parts and compared against the
+//! `data/answers` manifest. A fixture named `{prefix}_dayNN` is solved as day
+//! `NN` while a fixture without day suffix (e.g. `empty`) is solved as every
+//! day owning at least one fixture. Fixtures of days whose cargo feature is
+//! disabled are ignored, as well as fixtures whose answer is still pending.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use advent_of_code_2022::answers::{Answers, Outcome};
+use advent_of_code_2022::normalize::Normalization;
+use advent_of_code_2022::solver::{Input, PuzzlePart, Solve, Solver};
+use clap::ValueEnum;
+use libtest_mimic::{Arguments, Failed, Trial};
+
+const DATA_DIRECTORY: &str = "data";
+const ANSWERS_FILE: &str = "answers";
+
+fn check_fixture(path: &Path, day: u16, puzzle_part: PuzzlePart, expected: Option<Outcome>) -> Result<(), Failed> {
+    let expected = expected.ok_or("no recorded answer in 'data/answers'")?;
+    let actual = Outcome::from_solution(
+        &Input::file(path)
+            .with_normalization(Normalization::Strict)
+            .parse(day)
+            .and_then(|solver| solver.solve(puzzle_part)),
+    );
+
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!("answer differs from the recorded one:\n{}", expected.diff(&actual)).into())
+    }
+}
+
+fn main() {
+    let args = Arguments::from_args();
+
+    let answers = Answers::load(&Path::new(DATA_DIRECTORY).join(ANSWERS_FILE)).expect("cannot load answers");
+
+    let mut fixtures = fs::read_dir(DATA_DIRECTORY)
+        .expect("cannot read data directory")
+        .map(|entry| entry.expect("cannot read data directory entry").path())
+        .filter(|path| path.is_file() && path.file_name().unwrap() != ANSWERS_FILE)
+        .map(|path| {
+            let day = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.rsplit_once("_day"))
+                .and_then(|(_, day)| day.parse::<u16>().ok());
+
+            (path, day)
+        })
+        .collect::<Vec<_>>();
+
+    fixtures.sort();
+
+    let days = fixtures.iter().filter_map(|(_, day)| *day).collect::<BTreeSet<_>>();
+    let answers = &answers;
+    // fixtures of the days left out by the cargo features are ignored
+    let compiled_days = Solver::PUZZLES.iter().map(|puzzle| puzzle.day).collect::<BTreeSet<_>>();
+    let compiled_days = &compiled_days;
+
+    let trials = fixtures
+        .into_iter()
+        .flat_map(|(path, day)| {
+            let days = day
+                .map(|day| vec![day])
+                .unwrap_or_else(|| days.iter().copied().collect());
+
+            days.into_iter().flat_map(move |day| {
+                let path = path.clone();
+
+                PuzzlePart::value_variants().iter().map(move |puzzle_part| {
+                    let fixture = path.file_name().unwrap().to_string_lossy().to_string();
+                    let name = format!("{fixture}::day{day:02}::{puzzle_part}");
+                    let expected = answers.get(&fixture, day, *puzzle_part).cloned();
+                    let (path, puzzle_part) = (path.clone(), *puzzle_part);
+
+                    // answers still pending, e.g. of a scaffolded day, are not checked yet
+                    let ignored = !compiled_days.contains(&day) || expected == Some(Outcome::Pending);
+
+                    Trial::test(name, move || check_fixture(&path, day, puzzle_part, expected))
+                        .with_ignored_flag(ignored)
+                })
+            })
+        })
+        .collect();
+
+    libtest_mimic::run(&args, trials).exit();
+}
@@ -0,0 +1,145 @@
+//! Normalization of inputs written by other editors or platforms.
+//!
+//! Generated inputs with CRLF line endings, trailing whitespace and no final newline must give the same answers
+//! as the original ones, unless parsed strictly. Normalizing the valid fixtures of the `data/` directory must not
+//! change their answers either.
+
+use std::fs;
+
+use advent_of_code_2022::answers::Outcome;
+use advent_of_code_2022::generator;
+use advent_of_code_2022::normalize::Normalization;
+use advent_of_code_2022::solver::{Input, Parameters, Solve, Solver};
+use itertools::Itertools;
+
+const SEEDS: u64 = 3;
+const SIZES: &[usize] = &[0, 50];
+
+/// Ends lines with CRLF and trailing whitespace, dropping the final newline.
+fn mangle(input: &[u8]) -> Vec<u8> {
+    let input = String::from_utf8(input.to_vec()).expect("generated input is not UTF-8");
+
+    // an empty input has no line to mangle
+    if input.is_empty() {
+        return Vec::default();
+    }
+
+    input
+        .trim_end_matches('\n')
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| format!("{line}{}", [" ", "\t", "  ", ""][i % 4]))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        .into_bytes()
+}
+
+#[test]
+fn mangled_inputs_give_the_same_answers() {
+    for puzzle in Solver::PUZZLES {
+        for (seed, size) in (0..SEEDS).cartesian_product(SIZES.iter().copied()) {
+            let mut buffer = Vec::default();
+
+            generator::generate(puzzle.day, seed, size, &mut buffer).expect("cannot generate input");
+
+            let original = Input::memory(buffer.clone());
+            let mangled = Input::memory(mangle(&buffer));
+
+            for puzzle_part in puzzle.parts.iter().copied() {
+                let solve = |input: &Input| {
+                    Outcome::from_solution(&input.parse(puzzle.day).and_then(|solver| solver.solve(puzzle_part)))
+                };
+                let expected = solve(&original);
+
+                // scaffolded days have no solution yet
+                assert!(
+                    !matches!(&expected, Outcome::Error(kind) if kind != "NoSolution"),
+                    "day {} seed {seed} size {size} part {puzzle_part}: generated input is invalid ({expected})",
+                    puzzle.day
+                );
+                assert!(
+                    expected == solve(&mangled),
+                    "day {} seed {seed} size {size} part {puzzle_part}:\n{}",
+                    puzzle.day,
+                    expected.diff(&solve(&mangled))
+                );
+
+                if puzzle.stream.is_some() {
+                    let streaming =
+                        Outcome::from_solution(&mangled.solve_streaming(puzzle.day, &Parameters::preset(puzzle_part)));
+
+                    assert!(
+                        expected == streaming,
+                        "day {} seed {seed} size {size} part {puzzle_part} while streaming:\n{}",
+                        puzzle.day,
+                        expected.diff(&streaming)
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn strict_inputs_are_read_as_is() {
+    for day in [1, 2, 3]
+        .into_iter()
+        .filter(|day| Solver::PUZZLES.iter().any(|puzzle| puzzle.day == *day))
+    {
+        for size in SIZES.iter().copied() {
+            let mut buffer = Vec::default();
+
+            generator::generate(day, 0, size, &mut buffer).expect("cannot generate input");
+
+            let input = Input::memory(mangle(&buffer)).with_normalization(Normalization::Strict);
+
+            assert!(
+                input.parse(day).is_err(),
+                "day {day} size {size}: mangled input parsed strictly"
+            );
+        }
+    }
+}
+
+#[test]
+fn valid_fixtures_give_the_same_answers_when_normalized() {
+    let mut fixtures = fs::read_dir("data")
+        .expect("cannot read data directory")
+        .map(|entry| entry.expect("cannot read data directory entry").path())
+        .collect::<Vec<_>>();
+
+    fixtures.sort();
+
+    for puzzle in Solver::PUZZLES {
+        let suffix = format!("_day{:02}", puzzle.day);
+
+        for path in fixtures.iter().filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+
+            name.ends_with(&suffix) || name == "empty"
+        }) {
+            for puzzle_part in puzzle.parts.iter().copied() {
+                let solve = |normalization| {
+                    Outcome::from_solution(
+                        &Input::file(path.clone())
+                            .with_normalization(normalization)
+                            .parse(puzzle.day)
+                            .and_then(|solver| solver.solve(puzzle_part)),
+                    )
+                };
+                let expected = solve(Normalization::Strict);
+
+                if let Outcome::Answer(_) = expected {
+                    let normalized = solve(Normalization::Tolerant);
+
+                    assert!(
+                        expected == normalized,
+                        "{} part {puzzle_part}:\n{}",
+                        path.display(),
+                        expected.diff(&normalized)
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Overrides of the puzzle parameters.
+//!
+//! Overriding a parameter with the default of the other puzzle part must give the answer of that part, and invalid
+//! assignments must be rejected before solving.
+
+use advent_of_code_2022::answers::Outcome;
+use advent_of_code_2022::solver::{Input, Parameters, Puzzle, PuzzlePart, Solve, Solver};
+use advent_of_code_2022::trace::NoTracer;
+
+fn solve(day: u16, puzzle_part: PuzzlePart, assignments: &[&str]) -> Outcome {
+    let assignments = assignments
+        .iter()
+        .map(|assignment| assignment.to_string())
+        .collect::<Vec<_>>();
+    let solution = Puzzle::find(day)
+        .and_then(|puzzle| Parameters::preset(puzzle_part).with_assignments(puzzle, &assignments))
+        .and_then(|parameters| {
+            let input = Input::file(format!("data/input_day{day:02}"));
+
+            input.parse(day)?.solve_with(&parameters, &mut NoTracer)
+        });
+
+    Outcome::from_solution(&solution)
+}
+
+#[test]
+fn other_part_defaults_give_other_part_answers() {
+    for puzzle in Solver::PUZZLES {
+        // parameters whose defaults differ between parts fully describe what sets parts apart
+        let differing = puzzle
+            .parameters
+            .iter()
+            .filter(|parameter| parameter.default_value(PuzzlePart::One) != parameter.default_value(PuzzlePart::Two))
+            .collect::<Vec<_>>();
+
+        if differing.is_empty() || puzzle.parts.len() < 2 {
+            continue;
+        }
+
+        let assignments = differing
+            .iter()
+            .map(|parameter| format!("{}={}", parameter.name, parameter.default_value(PuzzlePart::Two)))
+            .collect::<Vec<_>>();
+        let assignments = assignments.iter().map(String::as_str).collect::<Vec<_>>();
+
+        assert!(
+            solve(puzzle.day, PuzzlePart::One, &assignments) == solve(puzzle.day, PuzzlePart::Two, &[]),
+            "day {}: part one with {assignments:?} differs from part two",
+            puzzle.day
+        );
+    }
+}
+
+#[test]
+fn invalid_assignments_are_rejected() {
+    for assignments in [
+        &["knots"][..],
+        &["knots=0"],
+        &["knots=1001"],
+        &["knots=two"],
+        &["length=2"],
+    ] {
+        assert!(
+            matches!(solve(9, PuzzlePart::One, assignments), Outcome::Error(_)),
+            "{assignments:?} is accepted"
+        );
+    }
+}
+
+#[test]
+fn assignments_of_days_without_parameters_are_rejected() {
+    for day in [2, 3] {
+        assert!(
+            matches!(solve(day, PuzzlePart::One, &["knots=10"]), Outcome::Error(_)),
+            "day {day} accepts a parameter"
+        );
+        assert!(
+            matches!(solve(day, PuzzlePart::One, &[]), Outcome::Answer(_)),
+            "day {day} fails without assignments"
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Property tests checking invariants of the solvers on generated inputs.
+//!
+//! Inputs are drawn from the generator of the day, see [`generated`], or from a dedicated strategy exploring inputs
+//! the generator never writes. A failing input is shrunk, then saved as `{property}_dayNN` in the test target directory
+//! so that it can be copied as is into `data/`.
+//!
+//! Dedicated strategies draw structured records that shrink towards a minimal input, whereas generated inputs only
+//! shrink their number of records and their seed, so that their counterexamples may not be minimal.
+
+use std::fs;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use advent_of_code_2022::answer::Answer;
+#[cfg(feature = "day05")]
+use advent_of_code_2022::days::day05;
+#[cfg(feature = "day07")]
+use advent_of_code_2022::days::day07;
+use advent_of_code_2022::generator;
+#[cfg(feature = "day07")]
+use advent_of_code_2022::number::Natural;
+use advent_of_code_2022::solver::{PuzzlePart, Solve, Solver};
+use advent_of_code_2022::trace::NoTracer;
+use num_bigint::BigInt;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestError, TestRunner};
+
+/// Runs `property` on inputs drawn from `strategy`, saving the minimal failing input if any.
+fn check<S: Strategy<Value = String>>(
+    name: &str,
+    day: u16,
+    strategy: S,
+    property: impl Fn(&str) -> Result<(), TestCaseError>,
+) {
+    let mut runner = TestRunner::new(Config {
+        failure_persistence: None,
+        ..Config::default()
+    });
+
+    match runner.run(&strategy, |input| property(&input)) {
+        Ok(()) => {}
+        Err(TestError::Fail(reason, input)) => {
+            let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("properties");
+            let path = directory.join(format!("{name}_day{day:02}"));
+
+            fs::create_dir_all(&directory).expect("cannot create failing inputs directory");
+            fs::write(&path, input).expect("cannot save failing input");
+
+            panic!(
+                "{reason}\nminimal failing input saved into '{}', copy it into 'data/' to keep it as a fixture",
+                path.display()
+            );
+        }
+        Err(TestError::Abort(reason)) => panic!("{reason}"),
+    }
+}
+
+fn answer(day: u16, input: &str, puzzle_part: PuzzlePart) -> Result<Answer, TestCaseError> {
+    Solver::from_reader(day, input.as_bytes())
+        .and_then(|solver| solver.solve(puzzle_part))
+        .map_err(|e| TestCaseError::fail(format!("part {puzzle_part}: {e}")))
+}
+
+fn solve(day: u16, input: &str, puzzle_part: PuzzlePart) -> Result<BigInt, TestCaseError> {
+    answer(day, input, puzzle_part)?
+        .as_integer()
+        .ok_or_else(|| TestCaseError::fail(format!("part {puzzle_part}: answer is not an integer")))
+}
+
+/// Inputs of 1 to 63 records written by the generator of `day` from a drawn seed.
+///
+/// Only the number of records and the seed are shrunk, the content of a record not being drawn by proptest.
+fn generated(day: u16) -> impl Strategy<Value = String> {
+    (1..64usize, any::<u64>()).prop_map(move |(size, seed)| {
+        let mut input = Vec::default();
+
+        generator::generate(day, seed, size, &mut input).expect("cannot generate input");
+
+        String::from_utf8(input).expect("generated input is not UTF-8")
+    })
+}
+
+/// Pairs of section assignments.
+#[cfg(feature = "day04")]
+fn day04_input() -> impl Strategy<Value = String> {
+    let assignment = (1..100u32, 1..100u32).prop_map(|(a, b)| format!("{}-{}", a.min(b), a.max(b)));
+
+    vec((assignment.clone(), assignment), 1..50).prop_map(|pairs| {
+        pairs
+            .into_iter()
+            .map(|(first, second)| format!("{first},{second}\n"))
+            .collect()
+    })
+}
+
+/// A signal of letters among 12 ones followed by 14 distinct letters, so that both markers exist.
+#[cfg(feature = "day06")]
+fn day06_input() -> impl Strategy<Value = String> {
+    let prefix = vec(proptest::char::range('a', 'l'), 0..100);
+    let marker = Just(('a'..='z').collect::<Vec<_>>()).prop_shuffle();
+
+    (prefix, marker).prop_map(|(prefix, marker)| {
+        prefix
+            .into_iter()
+            .chain(marker.into_iter().take(14))
+            .chain(['\n'])
+            .collect()
+    })
+}
+
+#[cfg(feature = "day07")]
+#[derive(Debug, Clone)]
+enum Node {
+    File(u64),
+    Directory(Vec<Node>),
+}
+
+#[cfg(feature = "day07")]
+impl Node {
+    fn write_transcript(&self, input: &mut String) {
+        let Self::Directory(children) = self else {
+            return;
+        };
+
+        *input += "$ ls\n";
+
+        for (i, child) in children.iter().enumerate() {
+            match child {
+                Self::File(size) => *input += &format!("{size} f{i}\n"),
+                Self::Directory(_) => *input += &format!("dir d{i}\n"),
+            }
+        }
+
+        for (i, child) in children.iter().enumerate() {
+            if let Self::Directory(_) = child {
+                *input += &format!("$ cd d{i}\n");
+                child.write_transcript(input);
+                *input += "$ cd ..\n";
+            }
+        }
+    }
+}
+
+/// Transcript of the exploration of a file tree.
+#[cfg(feature = "day07")]
+fn day07_input() -> impl Strategy<Value = String> {
+    let tree = (1..1_000_000u64)
+        .prop_map(Node::File)
+        .prop_recursive(4, 64, 8, |node| vec(node, 0..8).prop_map(Node::Directory));
+
+    vec(tree, 0..8).prop_map(|children| {
+        let root = Node::Directory(children);
+        let mut input = "$ cd /\n".to_string();
+
+        root.write_transcript(&mut input);
+
+        input
+    })
+}
+
+/// Moves of the head of the rope.
+#[cfg(feature = "day09")]
+fn day09_input() -> impl Strategy<Value = String> {
+    let direction = prop_oneof![Just('R'), Just('L'), Just('U'), Just('D')];
+
+    vec((direction, 1..20u32), 0..50).prop_map(|moves| {
+        moves
+            .into_iter()
+            .map(|(direction, steps)| format!("{direction} {steps}\n"))
+            .collect()
+    })
+}
+
+#[cfg(feature = "day01")]
+#[test]
+fn day01_top_three_carry_more_than_top_one() {
+    check("calories", 1, generated(1), |input| {
+        let top_one = solve(1, input, PuzzlePart::One)?;
+        let top_three = solve(1, input, PuzzlePart::Two)?;
+
+        prop_assert!(top_three >= top_one, "{top_three} < {top_one}");
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "day02")]
+#[test]
+fn day02_rounds_score_1_to_9() {
+    check("scores", 2, generated(2), |input| {
+        let rounds = BigInt::from(input.lines().count());
+
+        for puzzle_part in [PuzzlePart::One, PuzzlePart::Two] {
+            let score = solve(2, input, puzzle_part)?;
+
+            prop_assert!(
+                score >= rounds && score <= &rounds * 9,
+                "part {puzzle_part}: score {score} for {rounds} round(s)"
+            );
+        }
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "day03")]
+#[test]
+fn day03_misplaced_items_are_1_to_52() {
+    check("priorities", 3, generated(3), |input| {
+        let rucksacks = BigInt::from(input.lines().count());
+        let priorities = solve(3, input, PuzzlePart::One)?;
+
+        // the compartments of a rucksack share exactly one item, whereas a group may share several badges
+        prop_assert!(
+            priorities >= rucksacks && priorities <= &rucksacks * 52,
+            "priorities {priorities} for {rucksacks} rucksack(s)"
+        );
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "day04")]
+#[test]
+fn day04_full_overlaps_are_partial_overlaps() {
+    check("overlaps", 4, day04_input(), |input| {
+        let full_overlaps = solve(4, input, PuzzlePart::One)?;
+        let partial_overlaps = solve(4, input, PuzzlePart::Two)?;
+
+        prop_assert!(
+            full_overlaps <= partial_overlaps,
+            "{full_overlaps} > {partial_overlaps}"
+        );
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "day05")]
+#[test]
+fn day05_moves_preserve_crates() {
+    check("crates", 5, generated(5), |input| {
+        let expected = input.matches('[').count();
+        let solver = day05::Solver::from_reader(BufReader::new(input.as_bytes()))
+            .map_err(|e| TestCaseError::fail(e.to_string()))?;
+
+        for stacks in [
+            solver.move_crates(day05::CrateMover9000, &mut NoTracer),
+            solver.move_crates(day05::CrateMover9001, &mut NoTracer),
+        ] {
+            let stacks = stacks.map_err(|e| TestCaseError::fail(e.to_string()))?;
+
+            prop_assert_eq!(stacks.values().map(|stack| stack.len()).sum::<usize>(), expected);
+        }
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "day06")]
+#[test]
+fn day06_message_marker_follows_packet_marker() {
+    check("markers", 6, day06_input(), |input| {
+        let packet = solve(6, input, PuzzlePart::One)?;
+        let message = solve(6, input, PuzzlePart::Two)?;
+
+        prop_assert!(message >= &packet + 10, "{message} < {packet} + 10");
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn day07_root_size_is_files_size() {
+    check("sizes", 7, day07_input(), |input| {
+        let expected = input
+            .lines()
+            .filter_map(|line| line.split_once(' ')?.0.parse::<u64>().ok())
+            .sum::<u64>();
+        let solver = day07::Solver::from_reader(BufReader::new(input.as_bytes()))
+            .map_err(|e| TestCaseError::fail(e.to_string()))?;
+        let actual = solver.used_space().map_err(|e| TestCaseError::fail(e.to_string()))?;
+
+        prop_assert_eq!(actual, Natural::from(expected));
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "day08")]
+#[test]
+fn day08_edge_trees_are_visible() {
+    check("visibility", 8, generated(8), |input| {
+        let side = input.lines().count();
+        let edge = BigInt::from(if side > 1 { 4 * (side - 1) } else { 1 });
+        let visible = solve(8, input, PuzzlePart::One)?;
+
+        prop_assert!(
+            visible >= edge && visible <= BigInt::from(side * side),
+            "{visible} visible tree(s) in a forest of {side} x {side} trees"
+        );
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "day09")]
+#[test]
+fn day09_tail_visits_at_most_one_position_per_step() {
+    check("visits", 9, day09_input(), |input| {
+        let steps = input
+            .lines()
+            .filter_map(|line| line.split_once(' ')?.1.parse::<u64>().ok())
+            .sum::<u64>();
+        let short_rope = solve(9, input, PuzzlePart::One)?;
+        let long_rope = solve(9, input, PuzzlePart::Two)?;
+
+        // the tail starts on a position, then leaves it at most once per step of the head
+        prop_assert!(
+            short_rope >= BigInt::from(1) && short_rope <= BigInt::from(steps + 1),
+            "{short_rope} visited position(s) for {steps} step(s)"
+        );
+        prop_assert!(
+            long_rope >= BigInt::from(1) && long_rope <= short_rope,
+            "{long_rope} visited position(s) by the long rope > {short_rope} by the short one"
+        );
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "day10")]
+#[test]
+fn day10_screen_is_40_by_6() {
+    check("screen", 10, generated(10), |input| {
+        let strength = solve(10, input, PuzzlePart::One)?;
+
+        // X stays within the screen, at most 39, during the 6 cycles 20, 60, ..., 220 summing to 720
+        prop_assert!(
+            strength >= BigInt::from(0) && strength <= BigInt::from(720 * 39),
+            "signal strength {strength}"
+        );
+
+        let Answer::Grid(rows) = answer(10, input, PuzzlePart::Two)? else {
+            return Err(TestCaseError::fail("part 2: answer is not a grid"));
+        };
+
+        prop_assert_eq!(rows.len(), 6);
+        prop_assert!(rows.iter().all(|row| row.len() == 40));
+
+        Ok(())
+    });
+}
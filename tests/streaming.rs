@@ -0,0 +1,163 @@
+//! Consistency of the streaming mode with the in-memory one.
+//!
+//! Every day able to stream its input must give the same answers as when parsing it first, on generated inputs of
+//! several sizes, on the fixtures of the `data/` directory and on edge cases. Invalid inputs must be rejected by both
+//! modes with the same kind of error, its message possibly differing since the streaming mode stops at the first
+//! invalid record.
+
+use std::fs;
+use std::path::Path;
+
+use advent_of_code_2022::answers::Outcome;
+use advent_of_code_2022::generator;
+use advent_of_code_2022::normalize::Normalization;
+use advent_of_code_2022::solver::{Input, Parameters, Puzzle, PuzzlePart, Solve, Solver};
+use itertools::Itertools;
+
+const SEEDS: u64 = 5;
+const SIZES: &[usize] = &[0, 1, 10, 500];
+
+fn streaming_puzzles() -> impl Iterator<Item = &'static Puzzle> {
+    Solver::PUZZLES.iter().filter(|puzzle| puzzle.stream.is_some())
+}
+
+fn outcomes(input: &Input, day: u16, puzzle_part: PuzzlePart) -> (Outcome, Outcome) {
+    let in_memory = input.parse(day).and_then(|solver| solver.solve(puzzle_part));
+    let streaming = input.solve_streaming(day, &Parameters::preset(puzzle_part));
+
+    (Outcome::from_solution(&in_memory), Outcome::from_solution(&streaming))
+}
+
+#[test]
+fn streaming_matches_in_memory_on_generated_inputs() {
+    for puzzle in streaming_puzzles() {
+        for seed in 0..SEEDS {
+            for size in SIZES.iter().copied() {
+                let mut buffer = Vec::default();
+
+                generator::generate(puzzle.day, seed, size, &mut buffer).expect("cannot generate input");
+
+                let input = Input::memory(buffer);
+
+                for puzzle_part in puzzle.parts.iter().copied() {
+                    let (in_memory, streaming) = outcomes(&input, puzzle.day, puzzle_part);
+
+                    assert!(
+                        matches!(in_memory, Outcome::Answer(_)),
+                        "day {} seed {seed} size {size} part {puzzle_part}: generated input is invalid ({in_memory})",
+                        puzzle.day
+                    );
+                    assert!(
+                        in_memory == streaming,
+                        "day {} seed {seed} size {size} part {puzzle_part}:\n{}",
+                        puzzle.day,
+                        in_memory.diff(&streaming)
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn streaming_matches_in_memory_on_fixtures() {
+    let mut fixtures = fs::read_dir("data")
+        .expect("cannot read data directory")
+        .map(|entry| entry.expect("cannot read data directory entry").path())
+        .collect::<Vec<_>>();
+
+    fixtures.sort();
+
+    for puzzle in streaming_puzzles() {
+        let suffix = format!("_day{:02}", puzzle.day);
+
+        for path in fixtures.iter().filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+
+            name.ends_with(&suffix) || name == "empty"
+        }) {
+            let input = Input::file(path.clone());
+
+            for puzzle_part in puzzle.parts.iter().copied() {
+                let (in_memory, streaming) = outcomes(&input, puzzle.day, puzzle_part);
+                let fixture = Path::new(path.file_name().unwrap()).display();
+
+                assert!(
+                    in_memory == streaming,
+                    "{fixture} part {puzzle_part}:\n{}",
+                    in_memory.diff(&streaming)
+                );
+            }
+        }
+    }
+}
+
+/// Inputs without any record, with non-ASCII characters or invalid UTF-8, or missing their final newline.
+const EDGE_CASES: &[&[u8]] = &[
+    b"",
+    b"\n",
+    b"\n\n",
+    "abcdéfghijklmnopqrstuvwxyz\n".as_bytes(),
+    "1000\n\n2000\n€\n".as_bytes(),
+    b"abcdefghij\xffklmnopqrstuvwxyz\n",
+    b"abcdefghijklmnopqrstuvwxyz\r",
+    b"abcdefghijklmnopqrstuvwxyz\r\n\r\n",
+    b"1000",
+];
+
+#[test]
+fn streaming_matches_in_memory_on_edge_cases() {
+    for puzzle in streaming_puzzles() {
+        for ((i, case), normalization) in EDGE_CASES
+            .iter()
+            .enumerate()
+            .cartesian_product([Normalization::Tolerant, Normalization::Strict])
+        {
+            let input = Input::memory(case.to_vec()).with_normalization(normalization);
+
+            for puzzle_part in puzzle.parts.iter().copied() {
+                let (in_memory, streaming) = outcomes(&input, puzzle.day, puzzle_part);
+
+                assert!(
+                    in_memory == streaming,
+                    "day {} edge case {i} ({normalization:?}) part {puzzle_part}:\n{}",
+                    puzzle.day,
+                    in_memory.diff(&streaming)
+                );
+            }
+        }
+    }
+}
+
+#[cfg(feature = "day06")]
+#[test]
+fn streaming_does_not_keep_extra_signals() {
+    use std::io::{self, BufReader, Read};
+
+    use advent_of_code_2022::days::day06;
+    use advent_of_code_2022::solver::Error;
+
+    // a second signal of 64 MiB, only its start being kept to locate the error
+    let payload = io::repeat(b'x').take(64 << 20);
+    let reader = BufReader::new(b"abcdefghijklmnopqrstuvwxyz\n".chain(payload).chain(&b"\nxyz\n"[..]));
+
+    match day06::Solver::solve_streaming(reader, &Parameters::preset(PuzzlePart::One)) {
+        Err(Error::InvalidInput(diagnostic)) => {
+            let location = diagnostic.location.expect("extra signal is not located");
+
+            assert_eq!(diagnostic.message, "expected only one signal (got 3 signals)");
+            assert_eq!(location.line, 2);
+            assert!(
+                location.source.len() <= 80,
+                "{} bytes of the extra signal kept",
+                location.source.len()
+            );
+        }
+        outcome => {
+            panic!(
+                "extra signals accepted ({:?})",
+                outcome.map(|answer| answer.to_string())
+            )
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Validation of whole inputs, reporting every malformed record instead of stopping at the first one.
+
+use std::fs;
+
+use advent_of_code_2022::answers::Outcome;
+use advent_of_code_2022::solver::{Error, Input, Solver};
+
+/// Line numbers of the errors reported when validating `input`.
+fn error_lines(day: u16, input: &str) -> Vec<Option<usize>> {
+    Input::memory(input.as_bytes().to_vec())
+        .validate(day)
+        .expect("cannot validate input")
+        .iter()
+        .map(|e| {
+            match e {
+                Error::InvalidInput(diagnostic) => diagnostic.location.as_ref().map(|location| location.line),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn every_malformed_record_is_reported() {
+    let cases: &[(u16, &str, &[usize])] = &[
+        (1, "100\nx\n200\n\n0\n5\n", &[2, 5]),
+        (2, "A X\nA Q\nB Y\nD Z\n", &[2, 4]),
+        (3, "abAB\nabc\nxyzz\n!!\n", &[2, 4]),
+        (4, "1-2,3-4\n1-x,3-4\n5-6,7-8\n9\n", &[2, 4]),
+        (
+            5,
+            "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\nmove x from 1 to 3\nfoo\n",
+            &[7, 8],
+        ),
+        (
+            7,
+            "$ cd /\n$ ls\ndir a\nfoo bar baz\n$ wat\n$ cd a\n$ ls\nx y.txt\n",
+            &[4, 5, 8],
+        ),
+        (8, "123\n1a3\nxyz\n", &[2, 3]),
+        (9, "R 4\nU x\nL 3\nQ 1\n", &[2, 4]),
+        (10, "noop\naddx 3\naddx\nfoo\n", &[3, 4]),
+    ];
+
+    for (day, input, lines) in cases {
+        if Solver::PUZZLES.iter().all(|puzzle| puzzle.day != *day) {
+            continue;
+        }
+
+        let expected = lines.iter().copied().map(Some).collect::<Vec<_>>();
+
+        assert_eq!(error_lines(*day, input), expected, "day {day}");
+    }
+}
+
+#[test]
+fn validation_agrees_with_parsing_on_fixtures() {
+    let mut fixtures = fs::read_dir("data")
+        .expect("cannot read data directory")
+        .map(|entry| entry.expect("cannot read data directory entry").path())
+        .collect::<Vec<_>>();
+
+    fixtures.sort();
+
+    for puzzle in Solver::PUZZLES {
+        let suffix = format!("_day{:02}", puzzle.day);
+
+        for path in fixtures.iter().filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(&suffix))
+        }) {
+            let input = Input::file(path.clone());
+            let parsed = input.parse(puzzle.day).map(|_| ());
+            let errors = input.validate(puzzle.day).expect("cannot validate input");
+
+            // inputs may parse and still fail to be solved, which validation does not cover
+            assert_eq!(
+                errors.is_empty(),
+                parsed.is_ok(),
+                "{}: parsed as {}, validated with {} error(s)",
+                path.display(),
+                parsed.map_or_else(
+                    |e| Outcome::Error(e.kind().to_string()).to_string(),
+                    |_| "ok".to_string()
+                ),
+                errors.len()
+            );
+        }
+    }
+}